@@ -0,0 +1,46 @@
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// Wakes the thread that is blocked in [`block_on`] by unparking it.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs a future to completion on the current thread, parking the thread while the future is pending.
+///
+/// This is a tiny, dependency-free executor meant for calling async code generated by [`fb!`](crate::fb) from synchronous code.
+/// It doesn't drive any I/O reactor or timers, so futures that rely on a runtime such as tokio still need that runtime.
+///
+/// # Usage
+///
+/// ```
+/// use flexi_func_declarative::{block_on, fb};
+///
+/// fb!(async, add, (a: u32, b: u32), -> u32, {
+///     a + b
+/// });
+///
+/// assert_eq!(block_on(add(2, 3)), 5);
+/// ```
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
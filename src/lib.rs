@@ -1,3 +1,7 @@
+mod executor;
+
+pub use executor::block_on;
+
 #[allow(unused_macros)]
 /// The `fb!` macro (Flexi Block) *or* (Function Builder) simplifies the generation of conditional synchronous or asynchronous functions within Rust code.
 ///
@@ -7,111 +11,2192 @@
 ///
 /// # Syntax
 ///
-/// ```
-/// fb!(mode, function_name, (parameter1: Type1, parameter2: Type2, ...), -> ReturnType, {
+/// ```text
+/// fb!([#[attributes]] mode, [error = ErrorType,] [visibility] [qualifiers] function_name[<generics>], (parameter1: Type1, parameter2: Type2, ...), [-> ReturnType,] [where clauses,] {
+///     // Function body
+/// });
+///
+/// fb!([#[attributes]] mode, [error = ErrorType,] [visibility] [qualifiers] fn function_name[<generics>](parameter1: Type1, ...) [-> ReturnType] [where clauses] {
 ///     // Function body
 /// });
+///
+/// fb! {
+///     [#[attributes]] mode {
+///         [#[attributes]] [visibility] [qualifiers] fn function_name[<generics>](parameter1: Type1, ...) [-> ReturnType] [where clauses] {
+///             // Function body
+///         }
+///         // More function definitions
+///     }
+/// }
 /// ```
 ///
 /// # Parameters
 ///
-/// - `mode`: A compile-time direct token that determines whether the generated function is synchronous (`sync`) or asynchronous (`async`).
-/// - `function_name`: The identifier for the generated function.
-/// - `parameters`: A comma-separated list of function parameters in the form `parameter_name: Type`.
-/// - `ReturnType`: The return type of the function.
+/// - `attributes`: Optional outer attributes and `///` doc comments, forwarded onto the generated function. On closures only attributes that are valid on a `let` statement (such as lint levels) can be used.
+/// - `mode`: A compile-time direct token that determines whether the generated function is synchronous (`sync`) or asynchronous (`async`). With `both`, a sync function and its async twin are generated from the same body. `async_boxed` returns the future as `Pin<Box<dyn Future<Output = ReturnType> + Send + 'fb>>`, and `async_boxed(?Send)` drops the `Send` bound. `async(send)` and `async(send + 'static)` generate regular async functions and closures whose futures, including every parameter they own, are checked to be `Send`, and `'static` when asked, at compile time. Inside a trait definition, `trait sync` and `trait async` generate methods, either with a default body or ending in `;`.
+/// - `ErrorType`: An optional error type for function definitions. The generated function returns `Result<ReturnType, ErrorType>` and the body's value is wrapped in `Ok`, so `?` can be used on any error convertible into `ErrorType`. An early `return` in the body has to return the full `Result`.
+/// - `function_name`: The identifier for the generated function, optionally preceded by a visibility modifier (`pub`, `pub(crate)`, `pub(super)`, `pub(in path)`).
+/// - `qualifiers`: Optional `const`, `unsafe` and `extern "ABI"` qualifiers, in that order. Async functions accept `unsafe` only, since `const` and `extern` functions can't be async.
+/// - `generics`: An optional generic parameter list (types, lifetimes and const parameters, with bounds) written right after the function name.
+/// - `parameters`: A comma-separated list of function parameters in the form `pattern: Type`, so `mut buf: Vec<u8>`, `(a, b): (u32, u32)` and `Point { x, y }: Point` all work and a trailing comma is allowed. Inside an `impl` block the list may start with a method receiver: `self`, `mut self`, `&self`, `&mut self`, `&'a self`, `&'a mut self` or a typed receiver such as `self: Pin<&mut Self>`.
+/// - `ReturnType`: The return type of the function. Leave the `-> ReturnType,` part out for functions returning `()`.
+/// - `where clauses`: An optional `where` clause, placed between the return type and the body.
 /// - `body`: The block of code that defines the function body.
 ///
+/// Invocations that match none of these forms fail with a `compile_error!` describing the likely mistake, such as an unknown mode, a missing `->`, a body that isn't a block or the mode and function name written the wrong way round.
+///
 /// # Usage
 ///
 /// Generating a synchronous function:
 ///
 /// ```
+/// use flexi_func_declarative::fb;
+///
 /// fb!(sync, greet, (name: String), -> String, {
 ///     format!("Hello, {}", name)
 /// });
+///
+/// assert_eq!(greet("ferris".to_string()), "Hello, ferris");
+/// ```
+///
+/// Generating an asynchronous function, here driven to completion with the built-in [`block_on`] executor:
+///
+/// ```
+/// use flexi_func_declarative::{block_on, fb};
+///
+/// async fn download(url: &str) -> Result<Vec<u8>, std::io::Error> {
+///     Ok(url.as_bytes().to_vec())
+/// }
+///
+/// fb!(async, fetch_data, (url: String), -> Result<String, std::io::Error>, {
+///     let bytes = download(&url).await?;
+///     Ok(String::from_utf8_lossy(&bytes).into_owned())
+/// });
+///
+/// assert_eq!(block_on(fetch_data("https://example.com".to_string())).unwrap(), "https://example.com");
+/// ```
+///
+/// Exposing a generated function as part of a public API:
+///
+/// ```
+/// # use flexi_func_declarative::fb;
+/// fb!(sync, pub(crate) add, (a: i32, b: i32), -> i32, {
+///     a + b
+/// });
+/// # assert_eq!(add(2, 3), 5);
+/// ```
+///
+/// Documenting a generated function and adding attributes to it:
+///
+/// ```
+/// # use flexi_func_declarative::fb;
+/// fb!(
+///     /// Returns the square of `x`.
+///     #[inline]
+///     #[must_use]
+///     sync, pub square, (x: u64), -> u64, {
+///         x * x
+///     }
+/// );
+/// # assert_eq!(square(4), 16);
+/// ```
+///
+/// Returning closures, optionally taking parameters and declaring a return type:
+///
+/// ```
+/// # use flexi_func_declarative::fb;
+/// let double = fb!(sync, closure, |x: u32| -> u32 { x * 2 });
+/// let greet = fb!(async, closure, |id: u64, name| {
+///     format!("{}: {}", id, name)
+/// });
+/// # assert_eq!(double(2), 4);
+/// # assert_eq!(flexi_func_declarative::block_on(greet(1, "ferris")), "1: ferris");
+/// ```
+///
+/// Executing a block immediately, either in place or as a future to await:
+///
+/// ```
+/// # use flexi_func_declarative::{block_on, fb};
+/// let sum = fb!(sync, execute, {
+///     let values = [1, 2, 3];
+///     values.iter().sum::<u32>()
+/// });
+/// assert_eq!(sum, 6);
+///
+/// let future = fb!(async, execute, {
+///     std::future::ready(sum * 2).await
+/// });
+/// assert_eq!(block_on(future), 12);
+/// ```
+///
+/// Choosing how closures and blocks capture their environment with `move` or `ref` in front of `closure` or `execute`:
+///
+/// ```
+/// # use flexi_func_declarative::fb;
+/// let label = String::from("worker");
+/// let job = fb!(sync, move closure, { label.len() });
+/// assert_eq!(std::thread::spawn(job).join().unwrap(), 6);
+///
+/// let mut log = Vec::new();
+/// let scoped = fb!(async, ref execute, { log.push("done") });
+/// flexi_func_declarative::block_on(scoped);
+/// assert_eq!(log, ["done"]);
+/// ```
+///
+/// Without a keyword, sync closures and blocks borrow while async ones move their captures into the returned future.
+/// An async `ref` closure borrows its environment but still moves its own parameters into the returned future.
+/// With `move`, a sync block runs inside an immediately called `move` closure, so `return` and `?` only leave the block.
+///
+/// Running an async block to completion from sync code with the built-in [`block_on`] executor:
+///
+/// ```
+/// # use flexi_func_declarative::fb;
+/// fb!(async, fetch_answer, (), -> u32, {
+///     42
+/// });
+///
+/// let answer = fb!(block_on, execute, {
+///     fetch_answer().await
+/// });
+/// assert_eq!(answer, 42);
+/// ```
+///
+/// Generating methods inside an `impl` block:
+///
+/// ```
+/// # use flexi_func_declarative::fb;
+/// struct Counter {
+///     count: u32,
+/// }
+///
+/// impl Counter {
+///     fb!(sync, pub increment, (&mut self, by: u32), -> u32, {
+///         self.count += by;
+///         self.count
+///     });
+///
+///     fb!(async, pub current, (&self), -> u32, {
+///         self.count
+///     });
+/// }
+/// # let mut counter = Counter { count: 0 };
+/// # assert_eq!(counter.increment(2), 2);
+/// # assert_eq!(flexi_func_declarative::block_on(counter.current()), 2);
+/// ```
+///
+/// Generating a generic function with a `where` clause:
+///
+/// ```
+/// # use flexi_func_declarative::fb;
+/// use std::str::FromStr;
+///
+/// fb!(sync, parse<T>, (s: &str), -> Result<T, T::Err>, where T: FromStr, {
+///     s.parse()
+/// });
+/// # assert_eq!(parse::<u8>("7"), Ok(7));
+/// ```
+///
+/// Writing the function as a regular Rust definition instead of comma-separated parts, which rustfmt and rust-analyzer handle better:
+///
+/// ```
+/// # use flexi_func_declarative::fb;
+/// fb!(sync, pub fn greet(name: String) -> String {
+///     format!("Hello, {}", name)
+/// });
+///
+/// fb!(both, fn [shout, shout_async](name: &str) -> String {
+///     name.to_uppercase()
+/// });
+/// # assert_eq!(greet("ferris".to_string()), "Hello, ferris");
+/// # assert_eq!(flexi_func_declarative::block_on(shout_async("hi")), "HI");
+/// ```
+///
+/// Generating trait methods, either required ones ending in `;` or ones with a default body.
+/// `trait async` methods return `impl Future<Output = ReturnType> + Send`, so callers can spawn the returned futures on multi-threaded executors, and implementors can write them as regular `async fn`s:
+///
+/// ```
+/// # use flexi_func_declarative::{block_on, fb};
+/// trait Storage: Sync {
+///     fb!(trait sync, fn load(&self, key: &str) -> Option<String>;);
+///
+///     fb!(trait async, fn load_async(&self, key: &str) -> Option<String>;);
+///
+///     fb!(trait async, fn load_or_default(&self, key: &str) -> String {
+///         self.load_async(key).await.unwrap_or_default()
+///     });
+/// }
+///
+/// struct Empty;
+///
+/// impl Storage for Empty {
+///     fb!(sync, fn load(&self, _key: &str) -> Option<String> {
+///         None
+///     });
+///
+///     fb!(async, fn load_async(&self, _key: &str) -> Option<String> {
+///         None
+///     });
+/// }
+///
+/// assert_eq!(block_on(Empty.load_or_default("key")), "");
+/// ```
+///
+/// The returned future borrows `&self`, so default bodies of `trait async` methods taking `&self` need the trait to require `Sync`.
+///
+/// Returning a boxed future, which can be stored or used in dyn-compatible traits.
+/// The macro declares a `'fb` lifetime and gives it to the borrowed receiver and to every reference without a lifetime in the parameter types, and each generic parameter gets a `'fb` bound, as does `Self` when the receiver is taken by value or typed.
+/// References inside typed receivers such as `self: Pin<&mut Self>` get it too, and so do the bounds of `impl Trait` parameters that aren't behind a reference:
+///
+/// ```
+/// # use flexi_func_declarative::{block_on, fb};
+/// trait Store {
+///     fb!(async_boxed, fn get(&self, key: &str) -> Option<u32>;);
+/// }
+///
+/// struct Fixed(u32);
+///
+/// impl Store for Fixed {
+///     fb!(async_boxed, fn get(&self, key: &str) -> Option<u32> {
+///         (key == "answer").then_some(self.0)
+///     });
+/// }
+///
+/// // `Rc` isn't `Send`, so this one opts out of the bound.
+/// fb!(async_boxed(?Send), fn read(value: std::rc::Rc<u32>) -> u32 {
+///     *value
+/// });
+///
+/// let store: Box<dyn Store> = Box::new(Fixed(42));
+/// assert_eq!(block_on(store.get("answer")), Some(42));
+/// assert_eq!(block_on(read(std::rc::Rc::new(7))), 7);
+/// ```
+///
+/// Checking at compile time that the future of an async function or closure is `Send`, with `async(send)`, or `Send + 'static`, with `async(send + 'static)`.
+/// The receiver and every parameter, used or not, are moved into the checked future before the body runs, so the function's future holds nothing besides it.
+/// A future holding an `Rc`, whether as a parameter or across an await point, then fails to compile with an error naming the function, instead of at the place where it gets spawned:
+///
+/// ```compile_fail
+/// # use flexi_func_declarative::fb;
+/// # async fn tick() {}
+/// fb!(async(send), fn cached(value: u32) -> u32 {
+///     let shared = std::rc::Rc::new(value);
+///     tick().await; // error: future cannot be sent between threads safely, required by a bound in `cached`
+///     *shared
+/// });
 /// ```
 ///
-/// Generating an asynchronous function:
+/// ```
+/// # use flexi_func_declarative::fb;
+/// fb!(async(send + 'static), fn total(values: Vec<u32>) -> u32 {
+///     values.into_iter().sum()
+/// });
+///
+/// let job = fb!(async(send), move closure, |extra: u32| -> u32 { extra * 2 });
+/// # assert_eq!(flexi_func_declarative::block_on(total(vec![1, 2])), 3);
+/// # assert_eq!(flexi_func_declarative::block_on(job(2)), 4);
+/// ```
+///
+/// Adding `const`, `unsafe` or `extern "C"` qualifiers, for example for FFI shims next to their async counterparts:
+///
+/// ```
+/// # use flexi_func_declarative::fb;
+/// fb!(sync, pub const fn limit() -> usize {
+///     64
+/// });
+///
+/// fb!(sync, pub unsafe extern "C" fn read_value(ptr: *const i32) -> i32 {
+///     *ptr
+/// });
+///
+/// fb!(async, pub unsafe fn read_value_async(ptr: *const i32) -> i32 {
+///     *ptr
+/// });
+/// # const LIMIT: usize = limit();
+/// # assert_eq!(LIMIT, 64);
+/// # assert_eq!(unsafe { read_value(&7) }, 7);
+/// ```
+///
+/// Mapping errors to a custom error type with `error = ErrorType`. The return type becomes `Result<ReturnType, ErrorType>` and the body's value is wrapped in `Ok`, so `?` works on any error that converts into `ErrorType`:
 ///
 /// ```
-/// fb!(async, fetch_data, (url: String), -> Result<String, reqwest::Error>, {
-///     reqwest::get(&url).await?.text().await
+/// # use flexi_func_declarative::fb;
+/// #[derive(Debug)]
+/// struct MyError(String);
+///
+/// impl From<std::num::ParseIntError> for MyError {
+///     fn from(error: std::num::ParseIntError) -> Self {
+///         MyError(error.to_string())
+///     }
+/// }
+///
+/// fb!(sync, error = MyError, parse_port, (s: &str), -> u16, {
+///     s.trim().parse()?
 /// });
+///
+/// fb!(async, error = MyError, parse_port_async, (s: &str), -> u16, {
+///     s.trim().parse()?
+/// });
+/// # assert_eq!(parse_port(" 8080 ").unwrap(), 8080);
+/// # assert!(flexi_func_declarative::block_on(parse_port_async("x")).is_err());
 /// ```
 ///
 /// # Tricks and Advanced Usage
 ///
+/// ## Sync and Async Twins
+///
+/// The `both` mode generates a synchronous function and an asynchronous one from a single body.
+/// `macro_rules!` can't build new identifiers, so both names are given in brackets, and everything else (visibility, attributes, generics, parameters) is shared.
+/// Inside the body, `fb_await!(expr)` marks an await point: it expands to `expr.await` in the async copy and to `expr` in the sync copy.
+/// Since the two copies usually call different functions, `fb_await!(sync_expr, async_expr)` picks `sync_expr` in the sync copy and `async_expr.await` in the async one.
+///
+/// ```
+/// # use flexi_func_declarative::fb;
+/// fb!(both, pub [load, load_async], (key: &str), -> usize, {
+///     key.len()
+/// });
+///
+/// fb!(both, pub [load_twice, load_twice_async], (key: &str), -> usize, {
+///     fb_await!(load(key), load_async(key)) * 2
+/// });
+/// # assert_eq!(load_twice("abc"), 6);
+/// # assert_eq!(flexi_func_declarative::block_on(load_twice_async("abc")), 6);
+/// ```
+///
+/// ## Blocking Wrappers
+///
+/// The `async_with_blocking` mode generates an async function together with a sync wrapper that blocks on it, so sync callers such as CLI tools and async services share one definition.
+/// As with `both`, the two names are given in brackets. The wrapper uses the built-in [`block_on`] executor unless another one is given as `async_with_blocking(executor)`, where `executor` is any function or closure taking the future and returning its output.
+/// The wrapper calls the async function and hands its future to the executor, so the body is only compiled once. Methods are called through `Self`; associated functions without a receiver write the async name as `Self::name` so the wrapper calls it the same way.
+///
+/// ```
+/// # use flexi_func_declarative::fb;
+/// use flexi_func_declarative::block_on;
+///
+/// fb!(async_with_blocking, pub [fetch, fetch_blocking], (url: String), -> usize, {
+///     url.len()
+/// });
+///
+/// fb!(async_with_blocking(|future| block_on(future)), pub [count, count_blocking], (items: Vec<u8>), -> usize, {
+///     items.len()
+/// });
+///
+/// struct Connection(usize);
+///
+/// impl Connection {
+///     fb!(async_with_blocking, pub [Self::open, open_blocking], (url: &str), -> Self, {
+///         Connection(url.len())
+///     });
+/// }
+///
+/// assert_eq!(fetch_blocking("https://example.com".to_string()), 19);
+/// assert_eq!(block_on(fetch("https://example.com".to_string())), 19);
+/// assert_eq!(Connection::open_blocking("https://example.com").0, 19);
+/// # assert_eq!(count_blocking(vec![1]), 1);
+/// ```
+///
 /// ## Conditional Compilation
 ///
 /// The `fb!` macro can be combined with Rust's conditional compilation features to selectively compile either the synchronous or asynchronous version of a function based on feature flags or target environment.
 ///
+/// The `cfg(...)` mode takes any `cfg` predicate and generates both variants from one body: an async function gated on the predicate and a sync one gated on its negation.
+/// Mark await points with `fb_await!` so the body fits both.
+///
 /// Example with feature flags:
 ///
 /// ```
-/// #[cfg(feature = "async")]
-/// fb!(async, process_data, (data: Vec<u8>), -> Result<(), MyError>, {
-///     // Asynchronous processing
+/// # use flexi_func_declarative::fb;
+/// fb!(cfg(feature = "async"), pub process_data, (data: Vec<u8>), -> usize, {
+///     data.len()
 /// });
 ///
-/// #[cfg(not(feature = "async"))]
-/// fb!(sync, process_data, (data: Vec<u8>), -> Result<(), MyError>, {
-///     // Synchronous processing
-/// });
+/// // Without the `async` feature, the sync version is the one that got compiled.
+/// # #[cfg(not(feature = "async"))]
+/// assert_eq!(process_data(vec![1, 2, 3]), 3);
 /// ```
 ///
+/// ## Declaring Many Functions at Once
+///
+/// The block form takes any number of function definitions, written in regular Rust syntax, and generates all of them in the `sync`, `async` or `cfg(...)` mode given in front of the block.
+/// Attributes placed before the mode are added to every function, while attributes inside the block only apply to the definition they precede.
+///
+/// ```
+/// # use flexi_func_declarative::fb;
+/// fb! {
+///     async {
+///         /// Fetches a user name.
+///         pub fn user_name(id: u64) -> String {
+///             format!("user-{}", id)
+///         }
+///
+///         pub fn user_names(ids: Vec<u64>) -> Vec<String> {
+///             let mut names = Vec::new();
+///             for id in ids {
+///                 names.push(user_name(id).await);
+///             }
+///             names
+///         }
+///     }
+/// }
+/// # assert_eq!(flexi_func_declarative::block_on(user_names(vec![1])), ["user-1"]);
+/// ```
+///
+/// Each definition in a block adds one level of macro recursion, so blocks of more than about 60 functions may need a higher `#![recursion_limit]` in the crate using them.
+///
 /// ## Leveraging Macros for DRY Principles
 ///
 /// You can define a wrapper macro around `fb!` to reduce repetition when declaring similar functions in different modes. This is especially handy when you have a set of functions that need to be available in both synchronous and asynchronous forms.
 ///
 /// Example:
 ///
-///
-///  macro_rules! define_greeting_fn {
-///     (sync) => {
-///         fb!(sync, greet, (name: String), -> String, {
-///             format!("Hello, {}", name)
-///         });
-///     };
-///     (async) => {
-///         fb!(async, greet, (name: String), -> String, {
+/// ```
+/// # use flexi_func_declarative::fb;
+/// macro_rules! define_greeting_fn {
+///     ($mode:tt) => {
+///         fb!($mode, pub greet, (name: String), -> String, {
 ///             format!("Hello, {}", name)
 ///         });
 ///     };
-///   }
-///
+/// }
 ///
 /// // Now, you can easily generate both versions with minimal repetition:
-/// define_greeting_fn!(sync);
-/// define_greeting_fn!(async);
+/// mod blocking {
+///     # use flexi_func_declarative::fb;
+///     define_greeting_fn!(sync);
+/// }
+///
+/// mod nonblocking {
+///     # use flexi_func_declarative::fb;
+///     define_greeting_fn!(async);
+/// }
+///
+/// assert_eq!(blocking::greet("ferris".to_string()), "Hello, ferris");
+/// assert_eq!(flexi_func_declarative::block_on(nonblocking::greet("ferris".to_string())), "Hello, ferris");
 /// ```
 ///
 /// By leveraging the `fb!` macro in your Rust projects, you can maintain cleaner and more maintainable codebases, especially when dealing with the complexities of synchronous and asynchronous programming patterns.
-
 // Improved macro definition for clarity and simplicity
 #[macro_export]
 macro_rules! fb {
-    // Pattern for async function definition
-    (async, $fn_name:ident, ($($param_name:ident : $param_type:ty),*), -> $return_type:ty, $body:block) => {
-        async fn $fn_name($($param_name : $param_type),*) -> $return_type $body
+    // Pattern for returning an async closure
+    ($(#[$meta:meta])* async, closure, $body:block) => {
+        $crate::fb! { @closure_start [async] [$(#[$meta])*] $body }
     };
-    // Pattern for sync function definition
-    (sync, $fn_name:ident, ($($param_name:ident : $param_type:ty),*), -> $return_type:ty, $body:block) => {
-        fn $fn_name($($param_name : $param_type),*) -> $return_type $body
+    ($(#[$meta:meta])* async, closure, || $($rest:tt)+) => {
+        $crate::fb! { @closure_start [async] [$(#[$meta])*] || $($rest)+ }
     };
-    // Pattern for returning an async closure
-    (async, closure, $body:block) => {
-        || async move $body
+    ($(#[$meta:meta])* async, closure, | $($rest:tt)+) => {
+        $crate::fb! { @closure_start [async] [$(#[$meta])*] | $($rest)+ }
+    };
+    ($(#[$meta:meta])* async, move closure, $($rest:tt)+) => {
+        $crate::fb! { @closure_start [async move] [$(#[$meta])*] $($rest)+ }
+    };
+    ($(#[$meta:meta])* async, ref closure, $($rest:tt)+) => {
+        $crate::fb! { @closure_start [async ref] [$(#[$meta])*] $($rest)+ }
     };
     // Pattern for returning a sync closure
-    (sync, closure, $body:block) => {
-        || $body
+    ($(#[$meta:meta])* sync, closure, $body:block) => {
+        $crate::fb! { @closure_start [sync] [$(#[$meta])*] $body }
+    };
+    ($(#[$meta:meta])* sync, closure, || $($rest:tt)+) => {
+        $crate::fb! { @closure_start [sync] [$(#[$meta])*] || $($rest)+ }
+    };
+    ($(#[$meta:meta])* sync, closure, | $($rest:tt)+) => {
+        $crate::fb! { @closure_start [sync] [$(#[$meta])*] | $($rest)+ }
+    };
+    ($(#[$meta:meta])* sync, move closure, $($rest:tt)+) => {
+        $crate::fb! { @closure_start [sync move] [$(#[$meta])*] $($rest)+ }
+    };
+    ($(#[$meta:meta])* sync, ref closure, $($rest:tt)+) => {
+        $crate::fb! { @closure_start [sync ref] [$(#[$meta])*] $($rest)+ }
     };
     // Pattern for immediate execution of an async block
     (async, execute, $body:block) => {
-        async move $body
+        $crate::fb! { @execute [async move] $body }
+    };
+    (async, move execute, $body:block) => {
+        $crate::fb! { @execute [async move] $body }
+    };
+    (async, ref execute, $body:block) => {
+        $crate::fb! { @execute [async ref] $body }
+    };
+    // Pattern for immediate execution of an async block on the current thread
+    (block_on, execute, $body:block) => {
+        $crate::block_on($crate::fb! { @execute [async move] $body })
+    };
+    (block_on, move execute, $body:block) => {
+        $crate::block_on($crate::fb! { @execute [async move] $body })
+    };
+    (block_on, ref execute, $body:block) => {
+        $crate::block_on($crate::fb! { @execute [async ref] $body })
     };
     // Pattern for immediate execution of a sync block
     (sync, execute, $body:block) => {
-        $body
+        $crate::fb! { @execute [sync ref] $body }
+    };
+    (sync, move execute, $body:block) => {
+        $crate::fb! { @execute [sync move] $body }
+    };
+    (sync, ref execute, $body:block) => {
+        $crate::fb! { @execute [sync ref] $body }
+    };
+    // Pattern for function definitions returning `Result<ReturnType, ErrorType>`
+    ($(#[$meta:meta])* async, error = $error_type:ty, $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [error [$error_type] [async]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* sync, error = $error_type:ty, $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [error [$error_type] [sync]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* async_boxed, error = $error_type:ty, $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [error [$error_type] [async_boxed [+ ::core::marker::Send]]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* async_boxed(?Send), error = $error_type:ty, $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [error [$error_type] [async_boxed []]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* async_with_blocking, error = $error_type:ty, $vis:vis $(fn)? [Self::$fn_name:ident, $blocking_fn_name:ident] $($rest:tt)*) => {
+        $crate::fb! { @generics [error [$error_type] [with_blocking $blocking_fn_name [$crate::block_on] [Self::]]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* async_with_blocking, error = $error_type:ty, $vis:vis $(fn)? [$fn_name:ident, $blocking_fn_name:ident] $($rest:tt)*) => {
+        $crate::fb! { @generics [error [$error_type] [with_blocking $blocking_fn_name [$crate::block_on] []]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* async_with_blocking($executor:expr), error = $error_type:ty, $vis:vis $(fn)? [Self::$fn_name:ident, $blocking_fn_name:ident] $($rest:tt)*) => {
+        $crate::fb! { @generics [error [$error_type] [with_blocking $blocking_fn_name [$executor] [Self::]]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* async_with_blocking($executor:expr), error = $error_type:ty, $vis:vis $(fn)? [$fn_name:ident, $blocking_fn_name:ident] $($rest:tt)*) => {
+        $crate::fb! { @generics [error [$error_type] [with_blocking $blocking_fn_name [$executor] []]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* cfg($($predicate:tt)*), error = $error_type:ty, $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [error [$error_type] [cfg [$($predicate)*]]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* both, error = $error_type:ty, $vis:vis $(fn)? [$fn_name:ident, $async_fn_name:ident] $($rest:tt)*) => {
+        $crate::fb! { @generics [error [$error_type] [both $async_fn_name]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    // Pattern for trait methods, with a default body or ending in `;`. Async ones return
+    // `impl Future<Output = ReturnType> + Send`
+    ($(#[$meta:meta])* trait async, error = $error_type:ty, $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [error [$error_type] [async trait]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* trait sync, error = $error_type:ty, $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [error [$error_type] [sync]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* trait async, $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [async trait] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* trait sync, $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [sync] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    // Pattern for async function definition
+    ($(#[$meta:meta])* async, $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [async] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    // Pattern for sync function definition
+    ($(#[$meta:meta])* sync, $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [sync] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    // Pattern for an async function returning a boxed future, `Send` unless `?Send` is given
+    ($(#[$meta:meta])* async_boxed, $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [async_boxed [+ ::core::marker::Send]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* async_boxed(?Send), $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [async_boxed []] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    // Pattern for an async function plus a sync wrapper that blocks on it
+    ($(#[$meta:meta])* async_with_blocking, $vis:vis $(fn)? [Self::$fn_name:ident, $blocking_fn_name:ident] $($rest:tt)*) => {
+        $crate::fb! { @generics [with_blocking $blocking_fn_name [$crate::block_on] [Self::]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* async_with_blocking, $vis:vis $(fn)? [$fn_name:ident, $blocking_fn_name:ident] $($rest:tt)*) => {
+        $crate::fb! { @generics [with_blocking $blocking_fn_name [$crate::block_on] []] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* async_with_blocking($executor:expr), $vis:vis $(fn)? [Self::$fn_name:ident, $blocking_fn_name:ident] $($rest:tt)*) => {
+        $crate::fb! { @generics [with_blocking $blocking_fn_name [$executor] [Self::]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    ($(#[$meta:meta])* async_with_blocking($executor:expr), $vis:vis $(fn)? [$fn_name:ident, $blocking_fn_name:ident] $($rest:tt)*) => {
+        $crate::fb! { @generics [with_blocking $blocking_fn_name [$executor] []] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    // Pattern for a function that is async when the `cfg` predicate holds and sync otherwise
+    ($(#[$meta:meta])* cfg($($predicate:tt)*), $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [cfg [$($predicate)*]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    // Pattern for a sync function and its async twin sharing one body
+    ($(#[$meta:meta])* both, $vis:vis $(fn)? [$fn_name:ident, $async_fn_name:ident] $($rest:tt)*) => {
+        $crate::fb! { @generics [both $async_fn_name] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    // Pattern for several function definitions, written in regular Rust syntax, sharing one mode
+    ($(#[$meta:meta])* async { $($definitions:tt)* }) => {
+        $crate::__fb_split! { [fb @batch [async] [$(#[$meta])*]] $($definitions)* }
+    };
+    ($(#[$meta:meta])* sync { $($definitions:tt)* }) => {
+        $crate::__fb_split! { [fb @batch [sync] [$(#[$meta])*]] $($definitions)* }
+    };
+    ($(#[$meta:meta])* cfg($($predicate:tt)*) { $($definitions:tt)* }) => {
+        $crate::__fb_split! { [fb @batch [cfg [$($predicate)*]] [$(#[$meta])*]] $($definitions)* }
+    };
+    // Pattern for async functions and closures whose futures are checked to be `Send` (and to
+    // outlive the given lifetime) at compile time
+    ($(#[$meta:meta])* async(send $(+ $lifetime:lifetime)?), $($rest:tt)*) => {
+        $crate::fb! { @send_mode [+ ::core::marker::Send $(+ $lifetime)?] [$(#[$meta])*] $($rest)* }
+    };
+    // Fallback for invocations matching none of the forms above, reporting the likely mistake
+    ($(#[$meta:meta])* $mode:ident $($rest:tt)*) => {
+        $crate::fb! { @invalid $mode $($rest)* }
+    };
+
+    // Internal: hands the bounds of the `send` flag to the closure and function rules.
+    (@send_mode $bounds:tt [$(#[$meta:meta])*] closure, $($rest:tt)+) => {
+        $crate::fb! { @closure_start [async [$bounds]] [$(#[$meta])*] $($rest)+ }
+    };
+    (@send_mode $bounds:tt [$(#[$meta:meta])*] move closure, $($rest:tt)+) => {
+        $crate::fb! { @closure_start [async move [$bounds]] [$(#[$meta])*] $($rest)+ }
+    };
+    (@send_mode $bounds:tt [$(#[$meta:meta])*] ref closure, $($rest:tt)+) => {
+        $crate::fb! { @closure_start [async ref [$bounds]] [$(#[$meta])*] $($rest)+ }
+    };
+    (@send_mode $bounds:tt [$(#[$meta:meta])*] error = $error_type:ty, $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [error [$error_type] [async [$bounds]]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    (@send_mode $bounds:tt [$(#[$meta:meta])*] $vis:vis $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [async [$bounds]] [$(#[$meta])* $vis $fn_name] [] [] $($rest)* }
+    };
+    (@send_mode $bounds:tt $attrs:tt $($rest:tt)*) => {
+        $crate::fb! { @invalid_mode async }
+    };
+
+    // Internal: builds the error message for an invalid invocation.
+    (@invalid $mode:ident, $(move)? $(ref)? execute $($rest:tt)*) => {
+        $crate::fb! { @invalid_body execute }
+    };
+    (@invalid $mode:ident, $(move)? $(ref)? closure $($rest:tt)*) => {
+        $crate::fb! { @invalid_body closure }
+    };
+    (@invalid $first:ident, $second:ident $($rest:tt)*) => {
+        $crate::fb! { @invalid_order $first $second }
+    };
+    (@invalid $mode:ident $(($($args:tt)*))?, ($($params:tt)*), $fn_name:ident $($rest:tt)*) => {
+        compile_error!(concat!(
+            "the function name goes before its parameters: write `fb!(", stringify!($mode $(($($args)*))?),
+            ", ", stringify!($fn_name), ", (", stringify!($($params)*), "), ...)`"
+        ));
+    };
+    (@invalid $mode:ident $($rest:tt)*) => {
+        $crate::fb! { @invalid_mode $mode }
+    };
+    (@invalid_order $fn_name:ident sync) => { $crate::fb! { @swapped [sync] $fn_name } };
+    (@invalid_order $fn_name:ident async) => { $crate::fb! { @swapped [async] $fn_name } };
+    (@invalid_order $fn_name:ident both) => { $crate::fb! { @swapped [both] $fn_name } };
+    (@invalid_order $fn_name:ident block_on) => { $crate::fb! { @swapped [block_on] $fn_name } };
+    (@invalid_order $fn_name:ident async_with_blocking) => { $crate::fb! { @swapped [async_with_blocking] $fn_name } };
+    (@invalid_order $fn_name:ident async_boxed) => { $crate::fb! { @swapped [async_boxed] $fn_name } };
+    (@invalid_order $fn_name:ident cfg) => { $crate::fb! { @swapped [cfg(...)] $fn_name } };
+    (@invalid_order $first:ident $second:ident) => {
+        $crate::fb! { @invalid_mode $first }
+    };
+    (@swapped [$($mode:tt)*] $fn_name:ident) => {
+        compile_error!(concat!(
+            "the mode goes first: write `fb!(", stringify!($($mode)*), ", ", stringify!($fn_name), ", ...)`"
+        ));
+    };
+    (@invalid_body $kind:ident) => {
+        compile_error!(concat!(
+            "expected a `{ ... }` block", $crate::fb!(@invalid_closure_params $kind), " after `", stringify!($kind), ",`"
+        ));
+    };
+    (@invalid_closure_params closure) => { " or `|parameters| { ... }`" };
+    (@invalid_closure_params execute) => { "" };
+    (@invalid_mode sync) => { $crate::fb! { @invalid_forms sync } };
+    (@invalid_mode async) => { $crate::fb! { @invalid_forms async } };
+    (@invalid_mode block_on) => {
+        compile_error!("invalid `fb!(block_on, ...)` invocation, expected `fb!(block_on, execute, { ... })`");
+    };
+    (@invalid_mode both) => { $crate::fb! { @invalid_twin_forms both [name, async_name] } };
+    (@invalid_mode async_with_blocking) => {
+        $crate::fb! { @invalid_twin_forms async_with_blocking [name, blocking_name] }
+    };
+    (@invalid_mode async_boxed) => {
+        compile_error!(concat!(
+            "invalid `fb!(async_boxed, ...)` invocation, expected one of:\n",
+            "    fb!(async_boxed, name, (param: Type, ...), -> ReturnType, { ... })\n",
+            "    fb!(async_boxed, fn name(param: Type, ...) -> ReturnType { ... })\n",
+            "    fb!(async_boxed(?Send), fn name(param: Type, ...) -> ReturnType { ... })"
+        ));
+    };
+    (@invalid_mode trait) => {
+        compile_error!("invalid `fb!(trait ...)` invocation, expected `fb!(trait sync, fn name(&self, ...) -> ReturnType ...)` or `fb!(trait async, ...)`");
+    };
+    (@invalid_mode cfg) => {
+        compile_error!(concat!(
+            "invalid `fb!(cfg(...), ...)` invocation, expected one of:\n",
+            "    fb!(cfg(predicate), name, (param: Type, ...), -> ReturnType, { ... })\n",
+            "    fb!(cfg(predicate), fn name(param: Type, ...) -> ReturnType { ... })\n",
+            "    fb! { cfg(predicate) { fn name(param: Type, ...) -> ReturnType { ... } ... } }"
+        ));
+    };
+    (@invalid_mode $mode:ident) => {
+        compile_error!(concat!(
+            "unknown `fb!` mode `", stringify!($mode), "`, expected one of ",
+            "`sync`, `async`, `async_boxed`, `both`, `async_with_blocking`, `cfg(...)`, `block_on`, `trait sync` or `trait async`"
+        ));
+    };
+    (@invalid_forms $mode:ident) => {
+        compile_error!(concat!(
+            "invalid `fb!(", stringify!($mode), ", ...)` invocation, expected one of:\n",
+            "    fb!(", stringify!($mode), ", name, (param: Type, ...), -> ReturnType, { ... })\n",
+            "    fb!(", stringify!($mode), ", fn name(param: Type, ...) -> ReturnType { ... })\n",
+            "    fb!(", stringify!($mode), ", closure, |param: Type, ...| { ... })\n",
+            "    fb!(", stringify!($mode), ", execute, { ... })\n",
+            "    fb! { ", stringify!($mode), " { fn name(param: Type, ...) -> ReturnType { ... } ... } }"
+        ));
+    };
+    (@invalid_twin_forms $mode:ident [$($names:tt)*]) => {
+        compile_error!(concat!(
+            "invalid `fb!(", stringify!($mode), ", ...)` invocation, expected one of:\n",
+            "    fb!(", stringify!($mode), ", [", stringify!($($names)*), "], (param: Type, ...), -> ReturnType, { ... })\n",
+            "    fb!(", stringify!($mode), ", fn [", stringify!($($names)*), "](param: Type, ...) -> ReturnType { ... })"
+        ));
+    };
+
+    // Internal: collects the closure parameters written between `|` and `|`, then the optional
+    // return type. A bare body means the closure takes no parameters.
+    (@closure_start $mode:tt $attrs:tt $body:block) => {
+        $crate::fb! { @closure_emit $mode $attrs [] [] $body }
+    };
+    (@closure_start $mode:tt $attrs:tt || $($rest:tt)+) => {
+        $crate::fb! { @closure_signature $mode $attrs [] $($rest)+ }
+    };
+    (@closure_start $mode:tt $attrs:tt | $($rest:tt)+) => {
+        $crate::fb! { @closure_params $mode $attrs [] $($rest)+ }
+    };
+    (@closure_start $mode:tt $attrs:tt $($rest:tt)*) => {
+        $crate::fb! { @invalid_body closure }
+    };
+    (@closure_params $mode:tt $attrs:tt [$($params:tt)*] | $($rest:tt)+) => {
+        $crate::fb! { @closure_signature $mode $attrs [$($params)*] $($rest)+ }
+    };
+    (@closure_params $mode:tt $attrs:tt [$($params:tt)*] $token:tt $($rest:tt)+) => {
+        $crate::fb! { @closure_params $mode $attrs [$($params)* $token] $($rest)+ }
+    };
+    (@closure_signature $mode:tt $attrs:tt $params:tt -> $return_type:ty $body:block) => {
+        $crate::fb! { @closure_emit $mode $attrs $params [$return_type] $body }
+    };
+    (@closure_signature $mode:tt $attrs:tt $params:tt $body:block) => {
+        $crate::fb! { @closure_emit $mode $attrs $params [] $body }
+    };
+    (@closure_signature $mode:tt $attrs:tt [$($params:tt)*] $($rest:tt)*) => {
+        compile_error!(concat!(
+            "expected the closure body as a `{ ... }` block after `|", stringify!($($params)*), "|`"
+        ));
+    };
+
+    // Internal: turns the capture keyword into the tokens placed before the closure and before
+    // its body. Async closures move their captures into the returned future unless `ref` is
+    // given, in which case only the parameters are moved in, while sync closures borrow unless
+    // `move` is given.
+    (@closure_emit [async] $($rest:tt)*) => {
+        $crate::fb! { @closure_async [] [async move] [] $($rest)* }
+    };
+    (@closure_emit [async move] $($rest:tt)*) => {
+        $crate::fb! { @closure_async [move] [async move] [] $($rest)* }
+    };
+    (@closure_emit [async ref] $attrs:tt [$($params:tt)*] $output:tt $body:block) => {
+        $crate::fb! { @closure_ref [[] $attrs $output $body] [] [] $($params)* }
+    };
+    (@closure_emit [async [$bounds:tt]] $($rest:tt)*) => {
+        $crate::fb! { @closure_async [] [async move] [$bounds] $($rest)* }
+    };
+    (@closure_emit [async move [$bounds:tt]] $($rest:tt)*) => {
+        $crate::fb! { @closure_async [move] [async move] [$bounds] $($rest)* }
+    };
+    (@closure_emit [async ref [$bounds:tt]] $attrs:tt [$($params:tt)*] $output:tt $body:block) => {
+        $crate::fb! { @closure_ref [[$bounds] $attrs $output $body] [] [] $($params)* }
+    };
+    (@closure_emit [sync] $($rest:tt)*) => {
+        $crate::fb! { @closure_sync [] $($rest)* }
+    };
+    (@closure_emit [sync move] $($rest:tt)*) => {
+        $crate::fb! { @closure_sync [move] $($rest)* }
+    };
+    (@closure_emit [sync ref] $($rest:tt)*) => {
+        $crate::fb! { @closure_sync [] $($rest)* }
+    };
+
+    // Internal: emits the closure. Async closures can't annotate their return type, so the
+    // output of the body is bound to a typed local inside the returned future instead.
+    (@closure_async [$($capture:tt)?] [$($block:tt)+] [$($bounds:tt)?] $attrs:tt [$($params:tt)*] [] $body:block) => {
+        $crate::fb! {
+            @closure $attrs $($capture)? |$($params)*| $crate::fb!(@future [$($bounds)?] closure [$($block)+] {
+                #[allow(unused_imports)]
+                use $crate::__fb_await_async as fb_await;
+                $body
+            })
+        }
+    };
+    (@closure_async [$($capture:tt)?] [$($block:tt)+] [$($bounds:tt)?] $attrs:tt [$($params:tt)*] [$return_type:ty] $body:block) => {
+        $crate::fb! {
+            @closure $attrs $($capture)? |$($params)*| $crate::fb!(@future [$($bounds)?] closure [$($block)+] {
+                #[allow(unused_imports)]
+                use $crate::__fb_await_async as fb_await;
+                let output: $return_type = $body;
+                output
+            })
+        }
+    };
+    // Internal: emits an async `ref` closure. Its parameters are bound to names of their own and
+    // handed to the future in a `Cell`, which isn't `Copy`, so taking them out moves the cell into
+    // the future while the environment stays borrowed.
+    (@closure_ref $context:tt [$($renamed:tt)*] [$($pat:tt)+] : $param_type:ty $(, $($rest:tt)*)?) => {
+        $crate::fb! { @closure_ref $context [$($renamed)* [[$($pat)+] param: $param_type]] [] $($($rest)*)? }
+    };
+    (@closure_ref $context:tt [$($renamed:tt)*] [$($pat:tt)+] $(, $($rest:tt)*)?) => {
+        $crate::fb! { @closure_ref $context [$($renamed)* [[$($pat)+] param]] [] $($($rest)*)? }
+    };
+    (@closure_ref $context:tt $renamed:tt [$($pat:tt)*] $token:tt $($rest:tt)*) => {
+        $crate::fb! { @closure_ref $context $renamed [$($pat)* $token] $($rest)* }
+    };
+    (@closure_ref [[$($bounds:tt)?] $attrs:tt [$($return_type:ty)?] $body:block] [$([[$($pat:tt)*] $param:ident $(: $param_type:ty)?])*] []) => {
+        $crate::fb! {
+            @closure $attrs |$($param $(: $param_type)?),*| {
+                let params = ::core::cell::Cell::new(($($param,)*));
+                $crate::fb!(@future [$($bounds)?] closure [async] {
+                    let ($($($pat)*,)*) = params.into_inner();
+                    #[allow(unused_imports)]
+                    use $crate::__fb_await_async as fb_await;
+                    let output $(: $return_type)? = $body;
+                    output
+                })
+            }
+        }
+    };
+    (@closure_sync [$($capture:tt)?] $attrs:tt [$($params:tt)*] [$($return_type:ty)?] $body:block) => {
+        $crate::fb! {
+            @closure $attrs $($capture)? |$($params)*| $(-> $return_type)? {
+                #[allow(unused_imports)]
+                use $crate::__fb_await_sync as fb_await;
+                $body
+            }
+        }
+    };
+
+    // Internal: checks that a future satisfies the `send` bounds with `__fb_send_future!`,
+    // which names the check after the function so a failing bound points at it.
+    (@future [] $name:ident [$($block:tt)+] { $($body:tt)* }) => {
+        $($block)+ { $($body)* }
+    };
+    (@future [$bounds:tt] $name:ident $block:tt { $($body:tt)* }) => {
+        $crate::__fb_send_future! { $bounds $name $block { $($body)* } }
+    };
+
+    // Internal: emits an immediately executed block. A sync block can't own its captures, so
+    // `move` runs it through an immediately called `move` closure instead.
+    (@execute [async move] $body:block) => {
+        async move {
+            #[allow(unused_imports)]
+            use $crate::__fb_await_async as fb_await;
+            $body
+        }
+    };
+    (@execute [async ref] $body:block) => {
+        async {
+            #[allow(unused_imports)]
+            use $crate::__fb_await_async as fb_await;
+            $body
+        }
+    };
+    (@execute [sync move] $body:block) => {
+        (move || {
+            #[allow(unused_imports)]
+            use $crate::__fb_await_sync as fb_await;
+            $body
+        })()
+    };
+    (@execute [sync ref] $body:block) => {{
+        #[allow(unused_imports)]
+        use $crate::__fb_await_sync as fb_await;
+        $body
+    }};
+
+    // Internal: attributes can't sit on a closure expression on stable Rust, so they are
+    // put on a `let` statement binding the closure instead.
+    (@closure [] $closure:expr) => {
+        $closure
+    };
+    (@closure [$(#[$meta:meta])+] $closure:expr) => {{
+        $(#[$meta])+
+        let closure = $closure;
+        closure
+    }};
+
+    // Internal: declares one function of a batch. Definitions without qualifiers, generics or a
+    // `where` clause are handed over in a single step.
+    (@batch $mode:tt [$($attrs:tt)*] $(#[$meta:meta])* $vis:vis fn $fn_name:ident ($($params:tt)*) $(-> $return_type:ty)? $body:block) => {
+        $crate::fb! { @signature $mode [$($attrs)* $(#[$meta])* $vis $fn_name] [] ($($params)*) $(-> $return_type)? $body }
+    };
+    (@batch $mode:tt [$($attrs:tt)*] $(#[$meta:meta])* $vis:vis $keyword:ident $($rest:tt)*) => {
+        $crate::fb! { @generics $mode [$($attrs)* $(#[$meta])* $vis $keyword] [] [] $($rest)* }
+    };
+
+    // Internal: collects the generic parameter list following the function name,
+    // keeping track of nested angle brackets so bounds like `T: Into<Vec<u8>>` survive.
+    // With the `fn name(...)` syntax the `fn` keyword was taken as the name, and so were the
+    // `const`, `unsafe` and `extern "ABI"` qualifiers in front of it, so they are moved behind
+    // the real name first.
+    (@generics $mode:tt [$(#[$meta:meta])* $vis:vis fn $($qualifier:tt)*] [] [] $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics $mode [$(#[$meta])* $vis $fn_name $($qualifier)*] [] [] $($rest)* }
+    };
+    (@generics $mode:tt [$(#[$meta:meta])* $vis:vis const $($qualifier:tt)*] [] [] $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics $mode [$(#[$meta])* $vis $fn_name $($qualifier)* const] [] [] $($rest)* }
+    };
+    (@generics $mode:tt [$(#[$meta:meta])* $vis:vis unsafe $($qualifier:tt)*] [] [] $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics $mode [$(#[$meta])* $vis $fn_name $($qualifier)* unsafe] [] [] $($rest)* }
+    };
+    (@generics $mode:tt [$(#[$meta:meta])* $vis:vis extern $($qualifier:tt)*] [] [] $abi:literal $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics $mode [$(#[$meta])* $vis $fn_name $($qualifier)* extern $abi] [] [] $($rest)* }
+    };
+    (@generics $mode:tt [$(#[$meta:meta])* $vis:vis extern $($qualifier:tt)*] [] [] $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics $mode [$(#[$meta])* $vis $fn_name $($qualifier)* extern] [] [] $($rest)* }
+    };
+    (@generics $mode:tt $head:tt [] [] < $($rest:tt)*) => {
+        $crate::fb! { @generics $mode $head [] [<] $($rest)* }
+    };
+    (@generics $mode:tt $head:tt [$($generics:tt)*] [] , $($rest:tt)*) => {
+        $crate::fb! { @signature $mode $head [$($generics)*] $($rest)* }
+    };
+    (@generics $mode:tt $head:tt [$($generics:tt)*] [] ($($params:tt)*) $($rest:tt)*) => {
+        $crate::fb! { @signature $mode $head [$($generics)*] ($($params)*) $($rest)* }
+    };
+    (@generics $mode:tt $head:tt [$($generics:tt)*] [<] > $($rest:tt)*) => {
+        $crate::fb! { @generics $mode $head [$($generics)*] [] $($rest)* }
+    };
+    (@generics $mode:tt $head:tt [$($generics:tt)*] [< <] >> $($rest:tt)*) => {
+        $crate::fb! { @generics $mode $head [$($generics)* >] [] $($rest)* }
+    };
+    (@generics $mode:tt $head:tt [$($generics:tt)*] [< $($depth:tt)+] > $($rest:tt)*) => {
+        $crate::fb! { @generics $mode $head [$($generics)* >] [$($depth)+] $($rest)* }
+    };
+    (@generics $mode:tt $head:tt [$($generics:tt)*] [< < $($depth:tt)+] >> $($rest:tt)*) => {
+        $crate::fb! { @generics $mode $head [$($generics)* >>] [$($depth)+] $($rest)* }
+    };
+    (@generics $mode:tt $head:tt [$($generics:tt)*] [$($depth:tt)+] < $($rest:tt)*) => {
+        $crate::fb! { @generics $mode $head [$($generics)* <] [< $($depth)+] $($rest)* }
+    };
+    (@generics $mode:tt $head:tt [$($generics:tt)*] [$($depth:tt)+] << $($rest:tt)*) => {
+        $crate::fb! { @generics $mode $head [$($generics)* <<] [< < $($depth)+] $($rest)* }
+    };
+    (@generics $mode:tt $head:tt [$($generics:tt)*] [$($depth:tt)+] $token:tt $($rest:tt)*) => {
+        $crate::fb! { @generics $mode $head [$($generics)* $token] [$($depth)+] $($rest)* }
+    };
+    (@generics $mode:tt [$(#[$meta:meta])* $vis:vis move] [] [] $kind:ident $($rest:tt)*) => {
+        $crate::fb! { @invalid_body $kind }
+    };
+    (@generics $mode:tt [$(#[$meta:meta])* $vis:vis ref] [] [] $kind:ident $($rest:tt)*) => {
+        $crate::fb! { @invalid_body $kind }
+    };
+    (@generics $mode:tt [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] $generics:tt [] $($rest:tt)*) => {
+        compile_error!(concat!(
+            "expected the parameters of `", stringify!($fn_name), "` after its name, ",
+            "either as `, (name: Type, ...)` or as `(name: Type, ...)`"
+        ));
+    };
+
+    // Internal: splits the parameter list, the optional return type and the optional `where` clause,
+    // either comma-separated or written as a regular Rust function signature.
+    (@signature $mode:tt $head:tt $generics:tt $params:tt, -> $return_type:ty, where $($rest:tt)*) => {
+        $crate::fb! { @where $mode $head $generics $params [-> $return_type] [where] $($rest)* }
+    };
+    (@signature $mode:tt $head:tt $generics:tt $params:tt, -> $return_type:ty, $body:block) => {
+        $crate::fb! { @params $mode $head $generics $params [-> $return_type] [] $body }
+    };
+    (@signature $mode:tt $head:tt $generics:tt $params:tt, where $($rest:tt)*) => {
+        $crate::fb! { @where $mode $head $generics $params [] [where] $($rest)* }
+    };
+    (@signature $mode:tt $head:tt $generics:tt $params:tt, $body:block) => {
+        $crate::fb! { @params $mode $head $generics $params [] [] $body }
+    };
+    (@signature $mode:tt $head:tt $generics:tt $params:tt -> $return_type:ty where $($rest:tt)*) => {
+        $crate::fb! { @where $mode $head $generics $params [-> $return_type] [where] $($rest)* }
+    };
+    (@signature $mode:tt $head:tt $generics:tt $params:tt -> $return_type:ty $body:block) => {
+        $crate::fb! { @params $mode $head $generics $params [-> $return_type] [] $body }
+    };
+    (@signature $mode:tt $head:tt $generics:tt $params:tt where $($rest:tt)*) => {
+        $crate::fb! { @where $mode $head $generics $params [] [where] $($rest)* }
+    };
+    (@signature $mode:tt $head:tt $generics:tt $params:tt $body:block) => {
+        $crate::fb! { @params $mode $head $generics $params [] [] $body }
+    };
+    (@signature $mode:tt $head:tt $generics:tt $params:tt -> $return_type:ty;) => {
+        $crate::fb! { @params $mode $head $generics $params [-> $return_type] [] [;] }
+    };
+    (@signature $mode:tt $head:tt $generics:tt $params:tt;) => {
+        $crate::fb! { @params $mode $head $generics $params [] [] [;] }
+    };
+    (@signature $mode:tt [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] $generics:tt ($($params:tt)*), -> $return_type:ty, $($rest:tt)+) => {
+        compile_error!(concat!(
+            "expected the body of `", stringify!($fn_name), "` as a `{ ... }` block after `-> ",
+            stringify!($return_type), ",`"
+        ));
+    };
+    (@signature $mode:tt [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] $generics:tt ($($params:tt)*), -> $return_type:ty $(, $($rest:tt)*)?) => {
+        compile_error!(concat!(
+            "expected `,` and the body of `", stringify!($fn_name), "` as a `{ ... }` block after `-> ",
+            stringify!($return_type), "`"
+        ));
+    };
+    (@signature $mode:tt [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] $generics:tt ($($params:tt)*), $return_type:ty, $($rest:tt)*) => {
+        compile_error!(concat!(
+            "missing `->` before the return type of `", stringify!($fn_name), "`: write `-> ",
+            stringify!($return_type), ",`"
+        ));
+    };
+    (@signature $mode:tt [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] $generics:tt ($($params:tt)*) -> $($rest:tt)*) => {
+        compile_error!(concat!(
+            "expected the body of `", stringify!($fn_name), "` as a `{ ... }` block after its return type"
+        ));
+    };
+    (@signature $mode:tt [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] $generics:tt ($($params:tt)*) $return_type:ty $(, $($rest:tt)*)?) => {
+        compile_error!(concat!(
+            "missing `->` before the return type of `", stringify!($fn_name), "`: write `-> ",
+            stringify!($return_type), "`"
+        ));
+    };
+    (@signature $mode:tt [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] $generics:tt ($($params:tt)*) $($rest:tt)*) => {
+        compile_error!(concat!(
+            "expected `-> ReturnType`, a `where` clause or a `{ ... }` body after the parameters of `",
+            stringify!($fn_name), "`"
+        ));
+    };
+    (@signature $mode:tt [$(#[$meta:meta])* $vis:vis execute] [] $($rest:tt)*) => {
+        $crate::fb! { @invalid_body execute }
+    };
+    (@signature $mode:tt [$(#[$meta:meta])* $vis:vis closure] [] $($rest:tt)*) => {
+        $crate::fb! { @invalid_body closure }
+    };
+    (@signature $mode:tt [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] $generics:tt $($rest:tt)*) => {
+        compile_error!(concat!(
+            "expected the parameters of `", stringify!($fn_name), "` in parentheses, like `(name: Type, ...)`"
+        ));
+    };
+
+    // Internal: collects the `where` clause up to the function body, or the `;` ending a declaration.
+    (@where $mode:tt $head:tt $generics:tt $params:tt $return_type:tt [$($where:tt)*] { $($body:tt)* }) => {
+        $crate::fb! { @params $mode $head $generics $params $return_type [$($where)*] { $($body)* } }
+    };
+    (@where $mode:tt $head:tt $generics:tt $params:tt $return_type:tt [$($where:tt)*] ;) => {
+        $crate::fb! { @params $mode $head $generics $params $return_type [$($where)*] [;] }
+    };
+    (@where $mode:tt $head:tt $generics:tt $params:tt $return_type:tt [$($where:tt)*] $token:tt $($rest:tt)*) => {
+        $crate::fb! { @where $mode $head $generics $params $return_type [$($where)* $token] $($rest)* }
+    };
+    (@where $mode:tt [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] $generics:tt $params:tt $return_type:tt $where:tt) => {
+        compile_error!(concat!(
+            "expected the body of `", stringify!($fn_name), "` as a `{ ... }` block after its `where` clause"
+        ));
+    };
+
+    // Internal: separates an optional method receiver from the remaining parameters. The
+    // parameter list is passed twice: the first copy is matched against literal `self` forms
+    // and the second one supplies the caller's own tokens, keeping `self` usable in the body.
+    (@params $mode:tt $head:tt $generics:tt $params:tt $($rest:tt)*) => {
+        $crate::fb! { @receiver $params $params $mode $head $generics $($rest)* }
+    };
+    (@receiver ($(mut)? self : $($_x:tt)*) $params:tt [async_boxed $send:tt] $head:tt $generics:tt $($rest:tt)*) => {
+        $crate::fb! { @param_list [async_boxed $send] $head $generics [typed] [] [] $params $($rest)* }
+    };
+    (@receiver ($(mut)? self : $($_x:tt)*) $params:tt [error $error_type:tt [async_boxed $send:tt]] $head:tt $generics:tt $($rest:tt)*) => {
+        $crate::fb! { @param_list [error $error_type [async_boxed $send]] $head $generics [typed] [] [] $params $($rest)* }
+    };
+    (@receiver (self : $($_x:tt)*) ($self:tt : $self_type:ty $(, $($params:tt)*)?) $mode:tt $head:tt $generics:tt $($rest:tt)*) => {
+        $crate::fb! { @param_list $mode $head $generics [$self: $self_type,] [] [] ($($($params)*)?) $($rest)* }
+    };
+    (@receiver (mut self : $($_x:tt)*) ($mut:tt $self:tt : $self_type:ty $(, $($params:tt)*)?) $mode:tt $head:tt $generics:tt $($rest:tt)*) => {
+        $crate::fb! { @param_list $mode $head $generics [$mut $self: $self_type,] [] [] ($($($params)*)?) $($rest)* }
+    };
+    (@receiver (self $($_x:tt)*) ($self:tt $(, $($params:tt)*)?) $mode:tt $head:tt $generics:tt $($rest:tt)*) => {
+        $crate::fb! { @param_list $mode $head $generics [$self,] [] [] ($($($params)*)?) $($rest)* }
+    };
+    (@receiver (mut self $($_x:tt)*) ($mut:tt $self:tt $(, $($params:tt)*)?) $mode:tt $head:tt $generics:tt $($rest:tt)*) => {
+        $crate::fb! { @param_list $mode $head $generics [$mut $self,] [] [] ($($($params)*)?) $($rest)* }
+    };
+    (@receiver (& self $($_x:tt)*) ($amp:tt $self:tt $(, $($params:tt)*)?) $mode:tt $head:tt $generics:tt $($rest:tt)*) => {
+        $crate::fb! { @param_list $mode $head $generics [$amp $self,] [] [] ($($($params)*)?) $($rest)* }
+    };
+    (@receiver (& mut self $($_x:tt)*) ($amp:tt $mut:tt $self:tt $(, $($params:tt)*)?) $mode:tt $head:tt $generics:tt $($rest:tt)*) => {
+        $crate::fb! { @param_list $mode $head $generics [$amp $mut $self,] [] [] ($($($params)*)?) $($rest)* }
+    };
+    (@receiver (& $_lifetime:lifetime self $($_x:tt)*) ($amp:tt $lifetime:lifetime $self:tt $(, $($params:tt)*)?) $mode:tt $head:tt $generics:tt $($rest:tt)*) => {
+        $crate::fb! { @param_list $mode $head $generics [$amp $lifetime $self,] [] [] ($($($params)*)?) $($rest)* }
+    };
+    (@receiver (& $_lifetime:lifetime mut self $($_x:tt)*) ($amp:tt $lifetime:lifetime $mut:tt $self:tt $(, $($params:tt)*)?) $mode:tt $head:tt $generics:tt $($rest:tt)*) => {
+        $crate::fb! { @param_list $mode $head $generics [$amp $lifetime $mut $self,] [] [] ($($($params)*)?) $($rest)* }
+    };
+    (@receiver $_params:tt $params:tt $mode:tt $head:tt $generics:tt $($rest:tt)*) => {
+        $crate::fb! { @param_list $mode $head $generics [] [] [] $params $($rest)* }
+    };
+
+    // Internal: splits the remaining parameters into `pattern: Type` pairs. Patterns are collected
+    // token by token up to the top-level `:`, since `pat_param` fragments can't be followed by one.
+    (@param_list [async_boxed $send:tt] $head:tt $generics:tt $receiver:tt [] [] $params:tt $($rest:tt)*) => {
+        $crate::fb! { @boxed [boxed $send] $head $generics $receiver $params $($rest)* }
+    };
+    (@param_list [error $error_type:tt [async_boxed $send:tt]] $head:tt $generics:tt $receiver:tt [] [] $params:tt $($rest:tt)*) => {
+        $crate::fb! { @boxed [error $error_type [boxed $send]] $head $generics $receiver $params $($rest)* }
+    };
+    (@param_list $mode:tt $head:tt $generics:tt $receiver:tt [$($params:tt)*] [] () $($rest:tt)*) => {
+        $crate::fb! { @emit $mode $head $generics $receiver [$($params)*] $($rest)* }
+    };
+    (@param_list $mode:tt $head:tt $generics:tt $receiver:tt [$($params:tt)*] [$($pat:tt)+] (: $param_type:ty $(, $($tail:tt)*)?) $($rest:tt)*) => {
+        $crate::fb! { @param_list $mode $head $generics $receiver [$($params)* [$($pat)+]: $param_type,] [] ($($($tail)*)?) $($rest)* }
+    };
+    (@param_list $mode:tt $head:tt $generics:tt $receiver:tt [$($params:tt)*] [$($pat:tt)*] ($token:tt $($tail:tt)*) $($rest:tt)*) => {
+        $crate::fb! { @param_list $mode $head $generics $receiver [$($params)*] [$($pat)* $token] ($($tail)*) $($rest)* }
+    };
+
+    // Internal: ties every borrow of an `async_boxed` function to a `'fb` lifetime, so the boxed
+    // future can hold on to them. `'fb` is added to the generics, borrowed receivers and elided
+    // references in the parameter types get it, and each generic parameter must outlive it, as
+    // must `Self` when the receiver is taken by value or typed.
+    (@boxed $mode:tt $head:tt [$($generics:tt)*] $receiver:tt $params:tt $output:tt [$(where $($predicates:tt)*)?] $($body:tt)*) => {
+        $crate::fb! {
+            @boxed_bounds [$mode $head ['fb, $($generics)*] $receiver $params $output [$($($predicates)*)?] [$($body)*]]
+            [] [start] $($generics)*
+        }
+    };
+    (@boxed_bounds $context:tt [$($bounds:tt)*] [start] $lifetime:lifetime $($rest:tt)*) => {
+        $crate::fb! { @boxed_bounds $context [$($bounds)* $lifetime: 'fb,] [] $($rest)* }
+    };
+    (@boxed_bounds $context:tt $bounds:tt [start] const $($rest:tt)*) => {
+        $crate::fb! { @boxed_bounds $context $bounds [] $($rest)* }
+    };
+    (@boxed_bounds $context:tt [$($bounds:tt)*] [start] $param:ident $($rest:tt)*) => {
+        $crate::fb! { @boxed_bounds $context [$($bounds)* $param: 'fb,] [] $($rest)* }
+    };
+    (@boxed_bounds $context:tt $bounds:tt [] , $($rest:tt)*) => {
+        $crate::fb! { @boxed_bounds $context $bounds [start] $($rest)* }
+    };
+    (@boxed_bounds $context:tt $bounds:tt [$($depth:tt)*] < $($rest:tt)*) => {
+        $crate::fb! { @boxed_bounds $context $bounds [< $($depth)*] $($rest)* }
+    };
+    (@boxed_bounds $context:tt $bounds:tt [$($depth:tt)*] << $($rest:tt)*) => {
+        $crate::fb! { @boxed_bounds $context $bounds [< < $($depth)*] $($rest)* }
+    };
+    (@boxed_bounds $context:tt $bounds:tt [< $($depth:tt)*] > $($rest:tt)*) => {
+        $crate::fb! { @boxed_bounds $context $bounds [$($depth)*] $($rest)* }
+    };
+    (@boxed_bounds $context:tt $bounds:tt [< < $($depth:tt)*] >> $($rest:tt)*) => {
+        $crate::fb! { @boxed_bounds $context $bounds [$($depth)*] $($rest)* }
+    };
+    (@boxed_bounds $context:tt $bounds:tt $state:tt $token:tt $($rest:tt)*) => {
+        $crate::fb! { @boxed_bounds $context $bounds $state $($rest)* }
+    };
+    (@boxed_bounds [$mode:tt $head:tt $generics:tt $receiver:tt ($($params:tt)*) $output:tt [$($predicates:tt)*] [$($body:tt)*]] [$($bounds:tt)*] $state:tt) => {
+        $crate::fb! {
+            @boxed_receiver [$mode $head $generics [where $($bounds)* $($predicates)*] [$($body)*]]
+            $receiver [$output] [] [] [pattern] $($params)*
+        }
+    };
+    (@boxed_receiver $context:tt [& mut $self:tt,] $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context [& 'fb mut $self,] $($rest)* }
+    };
+    (@boxed_receiver $context:tt [& $self:tt,] $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context [& 'fb $self,] $($rest)* }
+    };
+    (@boxed_receiver $context:tt [$(& $($receiver:tt)*)?] $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context [$(& $($receiver)*)?] $($rest)* }
+    };
+    (@boxed_receiver [$mode:tt $head:tt $generics:tt [where $($predicates:tt)*] $body:tt] $receiver:tt $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs [$mode $head $generics [where Self: 'fb, $($predicates)*] $body] $receiver $($rest)* }
+    };
+
+    // Internal: copies the parameters, adding `'fb` to references without a lifetime in their
+    // types and to the bounds of `impl Trait` types that aren't behind a reference. Patterns are
+    // copied as they are, and parenthesized or bracketed types are entered by saving the tokens
+    // around them on a stack. A typed receiver is copied along with the parameters and split off
+    // again at the end.
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt [pattern] : $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* :] $stack [type] $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt [pattern] $token:tt $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* $token] $stack [pattern] $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt [type] , $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* ,] $stack [pattern] $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt [type $($depth:tt)*] < $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* <] $stack [type < $($depth)*] $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt [type $($depth:tt)*] << $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* <<] $stack [type < < $($depth)*] $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt [type < $($depth:tt)*] > $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* >] $stack [type $($depth)*] $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt [type < < $($depth:tt)*] >> $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* >>] $stack [type $($depth)*] $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt $state:tt & $lifetime:lifetime impl $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* & $lifetime impl] $stack $state $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt $state:tt & $lifetime:lifetime mut impl $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* & $lifetime mut impl] $stack $state $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt $state:tt & impl $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* & 'fb impl] $stack $state $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt $state:tt & mut impl $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* & 'fb mut impl] $stack $state $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt $state:tt impl $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* impl 'fb +] $stack $state $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt $state:tt & $lifetime:lifetime $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* & $lifetime] $stack $state $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt $state:tt && $lifetime:lifetime $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* & 'fb & $lifetime] $stack $state $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt $state:tt && $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* & 'fb & 'fb] $stack $state $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt $state:tt & $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* & 'fb] $stack $state $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] [$($stack:tt)*] $state:tt ($($group:tt)*) $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [] [[$($params)*] $state paren [$($rest)*] $($stack)*] [group] $($group)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] [$($stack:tt)*] $state:tt [$($group:tt)*] $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [] [[$($params)*] $state bracket [$($rest)*] $($stack)*] [group] $($group)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($params:tt)*] $stack:tt $state:tt $token:tt $($rest:tt)*) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* $token] $stack $state $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($group:tt)*] [[$($params:tt)*] $state:tt paren [$($rest:tt)*] $($stack:tt)*] [group]) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* ($($group)*)] [$($stack)*] $state $($rest)* }
+    };
+    (@boxed_refs $context:tt $receiver:tt $output:tt [$($group:tt)*] [[$($params:tt)*] $state:tt bracket [$($rest:tt)*] $($stack:tt)*] [group]) => {
+        $crate::fb! { @boxed_refs $context $receiver $output [$($params)* [$($group)*]] [$($stack)*] $state $($rest)* }
+    };
+    (@boxed_refs [$mode:tt $head:tt $generics:tt $where:tt [$($body:tt)*]] [typed] [$output:tt] [$($self:ident)+ : $self_type:ty $(, $($params:tt)*)?] [] $state:tt) => {
+        $crate::fb! { @param_list $mode $head $generics [$($self)+: $self_type,] [] [] ($($($params)*)?) $output $where $($body)* }
+    };
+    (@boxed_refs [$mode:tt $head:tt $generics:tt $where:tt [$($body:tt)*]] $receiver:tt [$output:tt] [$($params:tt)*] [] $state:tt) => {
+        $crate::fb! { @param_list $mode $head $generics $receiver [] [] ($($params)*) $output $where $($body)* }
+    };
+
+    // Internal: lists the names of the generic parameters, as lifetimes, type parameters and the
+    // type and const parameters in declaration order, then passes them as the first argument of
+    // the given internal rule.
+    (@generic_names $context:tt [$($lifetimes:tt)*] $types:tt $args:tt [start] $lifetime:lifetime $($rest:tt)*) => {
+        $crate::fb! { @generic_names $context [$($lifetimes)* $lifetime] $types $args [] $($rest)* }
+    };
+    (@generic_names $context:tt $lifetimes:tt $types:tt [$($args:tt)*] [start] const $param:ident $($rest:tt)*) => {
+        $crate::fb! { @generic_names $context $lifetimes $types [$($args)* $param] [] $($rest)* }
+    };
+    (@generic_names $context:tt $lifetimes:tt [$($types:tt)*] [$($args:tt)*] [start] $param:ident $($rest:tt)*) => {
+        $crate::fb! { @generic_names $context $lifetimes [$($types)* $param] [$($args)* $param] [] $($rest)* }
+    };
+    (@generic_names $context:tt $lifetimes:tt $types:tt $args:tt [] , $($rest:tt)*) => {
+        $crate::fb! { @generic_names $context $lifetimes $types $args [start] $($rest)* }
+    };
+    (@generic_names $context:tt $lifetimes:tt $types:tt $args:tt [$($depth:tt)*] < $($rest:tt)*) => {
+        $crate::fb! { @generic_names $context $lifetimes $types $args [< $($depth)*] $($rest)* }
+    };
+    (@generic_names $context:tt $lifetimes:tt $types:tt $args:tt [$($depth:tt)*] << $($rest:tt)*) => {
+        $crate::fb! { @generic_names $context $lifetimes $types $args [< < $($depth)*] $($rest)* }
+    };
+    (@generic_names $context:tt $lifetimes:tt $types:tt $args:tt [< $($depth:tt)*] > $($rest:tt)*) => {
+        $crate::fb! { @generic_names $context $lifetimes $types $args [$($depth)*] $($rest)* }
+    };
+    (@generic_names $context:tt $lifetimes:tt $types:tt $args:tt [< < $($depth:tt)*] >> $($rest:tt)*) => {
+        $crate::fb! { @generic_names $context $lifetimes $types $args [$($depth)*] $($rest)* }
+    };
+    (@generic_names $context:tt $lifetimes:tt $types:tt $args:tt $state:tt $token:tt $($rest:tt)*) => {
+        $crate::fb! { @generic_names $context $lifetimes $types $args $state $($rest)* }
+    };
+    (@generic_names [@$step:ident $($context:tt)*] $lifetimes:tt $types:tt $args:tt $state:tt) => {
+        $crate::fb! { @$step [$lifetimes $types $args] $($context)* }
+    };
+
+    // Internal: binds every parameter to a name of its own, so the whole parameter list can be
+    // forwarded to another function or moved into a future even when it holds patterns. Each
+    // `param` comes from a separate expansion, which keeps the names apart.
+    (@rename $context:tt [$($renamed:tt)*] [$($pat:tt)*]: $param_type:ty, $($rest:tt)*) => {
+        $crate::fb! { @rename $context [$($renamed)* [[$($pat)*] param: $param_type]] $($rest)* }
+    };
+    (@rename [@$step:ident $($context:tt)*] $renamed:tt) => {
+        $crate::fb! { @$step $($context)* $renamed }
+    };
+
+    // Internal: picks the caller's `self` token out of a method receiver.
+    (@receiver_self [& $self:ident,]) => { $self };
+    (@receiver_self [& mut $self:ident,]) => { $self };
+    (@receiver_self [& $lifetime:lifetime $self:ident,]) => { $self };
+    (@receiver_self [& $lifetime:lifetime mut $self:ident,]) => { $self };
+    (@receiver_self [mut $self:ident $(: $self_type:ty)?,]) => { $self };
+    (@receiver_self [$self:ident $(: $self_type:ty)?,]) => { $self };
+
+    // Internal: emits the sync wrapper of `async_with_blocking`, which hands the future of the
+    // async function to the executor. Methods are called through `Self`, other functions through
+    // the path given with the async name, if any.
+    (@blocking_params $names:tt $blocking:tt $head:tt $generics:tt $receiver:tt $output:tt $where:tt [$($params:tt)*]) => {
+        $crate::fb! { @rename [@blocking $names $blocking $head $generics $receiver $output $where] [] $($params)* }
+    };
+    (@blocking [$lifetimes:tt $types:tt [$($arg:ident)*]] [$blocking_fn_name:ident [$executor:expr] [$($path:tt)*]] [$(#[$meta:meta])* $vis:vis $fn_name:ident] [$($generics:tt)*] [] [$(-> $return_type:ty)?] [$($where:tt)*] [$([$pat:tt $param:ident: $param_type:ty])*]) => {
+        $(#[$meta])*
+        $vis fn $blocking_fn_name<$($generics)*>($($param: $param_type),*) $(-> $return_type)? $($where)* {
+            ($executor)($($path)* $fn_name::<$($arg),*>($($param),*))
+        }
+    };
+    (@blocking [$lifetimes:tt $types:tt [$($arg:ident)*]] [$blocking_fn_name:ident [$executor:expr] [$($path:tt)*]] [$(#[$meta:meta])* $vis:vis $fn_name:ident] [$($generics:tt)*] [$($receiver:tt)+] [$(-> $return_type:ty)?] [$($where:tt)*] [$([$pat:tt $param:ident: $param_type:ty])*]) => {
+        $(#[$meta])*
+        $vis fn $blocking_fn_name<$($generics)*>($($receiver)+ $($param: $param_type),*) $(-> $return_type)? $($where)* {
+            ($executor)(Self::$fn_name::<$($arg),*>($crate::fb!(@receiver_self [$($receiver)+]) $(, $param)*))
+        }
+    };
+
+    // Internal: emits an async function whose future is checked against the `send` bounds. The
+    // receiver and every parameter are moved into the checked future before anything else runs,
+    // so the future of the function holds nothing else across its await.
+    (@send $bounds:tt [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] [$($generics:tt)*] [$($receiver:tt)*] [-> $return_type:ty] [$($where:tt)*] $body:block [$([[$($pat:tt)*] $param:ident: $param_type:ty])*]) => {
+        $(#[$meta])*
+        $vis async $($qualifier)* fn $fn_name<$($generics)*>($($receiver)* $($param: $param_type),*) -> $return_type $($where)* {
+            $crate::fb!(@future [$bounds] $fn_name [async move] {
+                $crate::fb!(@capture_receiver [$($receiver)*]);
+                let ($($($pat)*,)*) = ($($param,)*);
+                #[allow(unused_imports)]
+                use $crate::__fb_await_async as fb_await;
+                let output: $return_type = $body;
+                output
+            })
+            .await
+        }
+    };
+    (@capture_receiver []) => {};
+    (@capture_receiver $receiver:tt) => {
+        let _ = &$crate::fb!(@receiver_self $receiver);
+    };
+
+    // Internal: emits the final function item.
+    (@emit [error [$error_type:ty] $mode:tt] $head:tt $generics:tt $receiver:tt $params:tt [-> $return_type:ty] $where:tt $($body:block)? $([$semicolon:tt])?) => {
+        $crate::fb! {
+            @emit $mode $head $generics $receiver $params [-> ::core::result::Result<$return_type, $error_type>] $where $({
+                let output: ::core::result::Result<$return_type, $error_type> = ::core::result::Result::Ok($body);
+                output
+            })? $([$semicolon])?
+        }
+    };
+    (@emit [error [$error_type:ty] $mode:tt] $head:tt $generics:tt $receiver:tt $params:tt [] $where:tt $($body:block)? $([$semicolon:tt])?) => {
+        $crate::fb! {
+            @emit $mode $head $generics $receiver $params [-> ::core::result::Result<(), $error_type>] $where $({
+                let output: ::core::result::Result<(), $error_type> = ::core::result::Result::Ok($body);
+                output
+            })? $([$semicolon])?
+        }
+    };
+    (@emit [both $async_fn_name:ident] [$(#[$meta:meta])* $vis:vis $fn_name:ident] $generics:tt $receiver:tt $params:tt $output:tt $where:tt $body:block) => {
+        $crate::fb! {
+            @emit [sync] [$(#[$meta])* $vis $fn_name] $generics $receiver $params $output $where $body
+        }
+        $crate::fb! {
+            @emit [async] [$(#[$meta])* $vis $async_fn_name] $generics $receiver $params $output $where $body
+        }
+    };
+    (@emit [cfg [$($predicate:tt)*]] [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] $generics:tt $receiver:tt $params:tt $output:tt $where:tt $body:block) => {
+        $crate::fb! {
+            @emit [async] [$(#[$meta])* #[cfg($($predicate)*)] $vis $fn_name $($qualifier)*] $generics $receiver $params $output $where $body
+        }
+        $crate::fb! {
+            @emit [sync] [$(#[$meta])* #[cfg(not($($predicate)*))] $vis $fn_name $($qualifier)*] $generics $receiver $params $output $where $body
+        }
+    };
+    (@emit [with_blocking $blocking_fn_name:ident [$executor:expr] $path:tt] [$(#[$meta:meta])* $vis:vis $fn_name:ident] [$($generics:tt)*] $receiver:tt [$($params:tt)*] $output:tt $where:tt $body:block) => {
+        $crate::fb! {
+            @emit [async] [$(#[$meta])* $vis $fn_name] [$($generics)*] $receiver [$($params)*] $output $where $body
+        }
+        $crate::fb! {
+            @generic_names [@blocking_params [$blocking_fn_name [$executor] $path] [$(#[$meta])* $vis $fn_name] [$($generics)*] $receiver $output $where [$($params)*]]
+            [] [] [] [start] $($generics)*
+        }
+    };
+    (@emit [async $($kind:tt)?] [$(#[$meta:meta])* $vis:vis $fn_name:ident const $($qualifier:tt)*] $($rest:tt)*) => {
+        compile_error!(concat!(
+            "`", stringify!($fn_name), "` can't be both `const` and `async`: ",
+            "remove `const` or generate it with the `sync` mode"
+        ));
+    };
+    (@emit [async $($kind:tt)?] [$(#[$meta:meta])* $vis:vis $fn_name:ident $(unsafe)? extern $($abi:literal)?] $($rest:tt)*) => {
+        compile_error!(concat!(
+            "`", stringify!($fn_name), "` can't be both `extern` and `async`: ",
+            "foreign code can't poll the returned future, so generate it with the `sync` mode"
+        ));
+    };
+    (@emit [async trait] $head:tt $generics:tt $receiver:tt $params:tt [] $($rest:tt)*) => {
+        $crate::fb! { @emit [async trait] $head $generics $receiver $params [-> ()] $($rest)* }
+    };
+    (@emit [async trait] [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] [$($generics:tt)*] [$($receiver:tt)*] [$([$($param:tt)*]: $param_type:ty,)*] [-> $return_type:ty] [$($where:tt)*] $($body:block)? $([$semicolon:tt])?) => {
+        $(#[$meta])*
+        $vis $($qualifier)* fn $fn_name<$($generics)*>($($receiver)* $($($param)* : $param_type),*)
+            -> impl ::core::future::Future<Output = $return_type> + ::core::marker::Send $($where)* $({
+            async move {
+                #[allow(unused_imports)]
+                use $crate::__fb_await_async as fb_await;
+                let output: $return_type = $body;
+                output
+            }
+        })? $($semicolon)?
+    };
+    (@emit [boxed $send:tt] $head:tt $generics:tt $receiver:tt $params:tt [] $($rest:tt)*) => {
+        $crate::fb! { @emit [boxed $send] $head $generics $receiver $params [-> ()] $($rest)* }
+    };
+    (@emit [boxed [$($send:tt)*]] [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] [$($generics:tt)*] [$($receiver:tt)*] [$([$($param:tt)*]: $param_type:ty,)*] [-> $return_type:ty] [$($where:tt)*] $($body:block)? $([$semicolon:tt])?) => {
+        $(#[$meta])*
+        $vis $($qualifier)* fn $fn_name<$($generics)*>($($receiver)* $($($param)* : $param_type),*)
+            -> ::core::pin::Pin<::std::boxed::Box<dyn ::core::future::Future<Output = $return_type> $($send)* + 'fb>>
+            $($where)* $({
+            ::std::boxed::Box::pin(async move {
+                #[allow(unused_imports)]
+                use $crate::__fb_await_async as fb_await;
+                let output: $return_type = $body;
+                output
+            })
+        })? $($semicolon)?
+    };
+    (@emit [async [$bounds:tt]] $head:tt $generics:tt $receiver:tt $params:tt [] $($rest:tt)*) => {
+        $crate::fb! { @emit [async [$bounds]] $head $generics $receiver $params [-> ()] $($rest)* }
+    };
+    (@emit [async [$bounds:tt]] $head:tt $generics:tt $receiver:tt [$($params:tt)*] $output:tt $where:tt $body:block) => {
+        $crate::fb! { @rename [@send $bounds $head $generics $receiver $output $where $body] [] $($params)* }
+    };
+    (@emit [async] [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] [$($generics:tt)*] [$($receiver:tt)*] [$([$($param:tt)*]: $param_type:ty,)*] [$(-> $return_type:ty)?] [$($where:tt)*] $($body:block)? $([$semicolon:tt])?) => {
+        $(#[$meta])*
+        $vis async $($qualifier)* fn $fn_name<$($generics)*>($($receiver)* $($($param)* : $param_type),*) $(-> $return_type)? $($where)* $({
+            #[allow(unused_imports)]
+            use $crate::__fb_await_async as fb_await;
+            $body
+        })? $($semicolon)?
+    };
+    (@emit [sync] [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] [$($generics:tt)*] [$($receiver:tt)*] [$([$($param:tt)*]: $param_type:ty,)*] [$(-> $return_type:ty)?] [$($where:tt)*] $($body:block)? $([$semicolon:tt])?) => {
+        $(#[$meta])*
+        $vis $($qualifier)* fn $fn_name<$($generics)*>($($receiver)* $($($param)* : $param_type),*) $(-> $return_type)? $($where)* $({
+            #[allow(unused_imports)]
+            use $crate::__fb_await_sync as fb_await;
+            $body
+        })? $($semicolon)?
+    };
+    (@emit $mode:tt [$(#[$meta:meta])* $vis:vis $fn_name:ident $($qualifier:tt)*] $generics:tt $receiver:tt $params:tt $output:tt $where:tt [;]) => {
+        compile_error!(concat!(
+            "expected the body of `", stringify!($fn_name), "` as a `{ ... }` block: ",
+            "declarations ending in `;` are only supported in the `sync`, `async`, `async_boxed`, `trait sync` and `trait async` modes"
+        ));
+    };
+}
+
+/// The `ff!` macro (Flexi Func) is the declarative counterpart of the `#[ff]` attribute from the flexi_func proc-macro crate.
+///
+/// It takes regular Rust function definitions and emits each of them twice: the sync function as written and an async twin sharing the same body.
+/// `macro_rules!` can't build new identifiers, so the name of the twin is given with an `#[ff(async_name = ...)]` attribute on each function; every other attribute, the visibility, generics, parameters, return type and `where` clause are shared.
+/// Inside the body, `fb_await!` marks await points exactly like in the `both` mode of [`fb!`].
+///
+/// This gives crates that can't take a proc-macro dependency the same sync/async pair.
+///
+/// # Usage
+///
+/// ```
+/// use flexi_func_declarative::{block_on, ff};
+///
+/// ff! {
+///     /// Counts the bytes in `data`.
+///     #[ff(async_name = compute_async)]
+///     pub fn compute(data: Vec<u8>) -> usize {
+///         data.len()
+///     }
+///
+///     #[ff(async_name = compute_twice_async)]
+///     pub fn compute_twice<T>(data: T) -> usize
+///     where
+///         T: Into<Vec<u8>> + Clone,
+///     {
+///         let data: Vec<u8> = data.into();
+///         fb_await!(compute(data.clone()), compute_async(data.clone())) * 2
+///     }
+/// }
+///
+/// assert_eq!(compute(vec![1, 2, 3]), 3);
+/// assert_eq!(block_on(compute_async(vec![1, 2, 3])), 3);
+/// assert_eq!(compute_twice("abc"), 6);
+/// ```
+///
+/// Each definition adds one level of macro recursion, so blocks of more than about 60 functions may need a higher `#![recursion_limit]` in the crate using them.
+#[macro_export]
+macro_rules! ff {
+    // Pattern for the end of the definitions
+    () => {};
+
+    // Internal: collects the attributes of a definition, setting the `#[ff(...)]` one aside.
+    (@attrs [$($attrs:tt)*] $async_name:tt #[ff(async_name = $name:ident)] $($rest:tt)*) => {
+        $crate::ff! { @attrs [$($attrs)*] [$name] $($rest)* }
+    };
+    (@attrs [$($attrs:tt)*] $async_name:tt #[$meta:meta] $($rest:tt)*) => {
+        $crate::ff! { @attrs [$($attrs)* #[$meta]] $async_name $($rest)* }
+    };
+    (@attrs $attrs:tt [] $vis:vis fn $fn_name:ident $($rest:tt)*) => {
+        compile_error!(concat!(
+            "missing `#[ff(async_name = ...)]` on `",
+            stringify!($fn_name),
+            "`: `ff!` needs the name of the async twin"
+        ));
+    };
+    // Functions without generics or a `where` clause are handed over in a single step.
+    (@attrs [$($attrs:tt)*] [$async_name:ident] $vis:vis fn $fn_name:ident ($($params:tt)*) $(-> $return_type:ty)? $body:block) => {
+        $crate::fb! { @signature [both $async_name] [$($attrs)* $vis $fn_name] [] ($($params)*) $(-> $return_type)? $body }
+    };
+    (@attrs [$($attrs:tt)*] [$async_name:ident] $vis:vis fn $fn_name:ident $($rest:tt)*) => {
+        $crate::fb! { @generics [both $async_name] [$($attrs)* $vis $fn_name] [] [] $($rest)* }
+    };
+
+    // Pattern for one or more function definitions
+    ($($definitions:tt)+) => {
+        $crate::__fb_split! { [ff @attrs [] []] $($definitions)+ }
+    };
+}
+
+/// The `fb_trait!` macro turns one trait definition into a blocking trait and its async counterpart.
+///
+/// Every method is generated with `fb!(trait sync, ...)` in the first trait and with `fb!(trait async, ...)` in the second one, so async methods return `impl Future<Output = ReturnType> + Send`.
+/// Required methods end in `;`, and default bodies are shared by both traits, using `fb_await!` for the await points.
+/// The name of the async trait is given with an `#[fb_trait(async_name = ...)]` attribute. Adding `blanket_impl` to it also implements the async trait for every `T: Trait + Sync` by calling the blocking method and wrapping its result in a ready future.
+///
+/// To keep the translation mechanical, the traits can't have generic parameters and methods take a `self`, `&self` or `&mut self` receiver followed by `name: Type` parameters, without generics or `where` clauses.
+/// Supertraits are copied to both traits.
+///
+/// # Usage
+///
+/// ```
+/// use flexi_func_declarative::{block_on, fb_trait};
+///
+/// fb_trait! {
+///     /// A key-value store.
+///     #[fb_trait(async_name = AsyncStore, blanket_impl)]
+///     pub trait Store: Sync {
+///         fn get(&self, key: &str) -> Option<String>;
+///
+///         fn get_or(&self, key: &str, default: String) -> String {
+///             fb_await!(self.get(key)).unwrap_or(default)
+///         }
+///     }
+/// }
+///
+/// struct Fixed;
+///
+/// impl Store for Fixed {
+///     fn get(&self, key: &str) -> Option<String> {
+///         (key == "answer").then(|| "42".to_string())
+///     }
+/// }
+///
+/// // Both traits are implemented, so calls name the one they want.
+/// assert_eq!(Store::get_or(&Fixed, "answer", String::new()), "42");
+/// // `AsyncStore` comes from the blanket implementation.
+/// assert_eq!(block_on(AsyncStore::get(&Fixed, "answer")), Some("42".to_string()));
+/// assert_eq!(block_on(AsyncStore::get_or(&Fixed, "other", "none".to_string())), "none");
+/// ```
+#[macro_export]
+macro_rules! fb_trait {
+    // Internal: collects the attributes of the trait, setting the `#[fb_trait(...)]` one aside.
+    (@attrs [$($attrs:tt)*] $config:tt #[fb_trait(async_name = $async_name:ident)] $($rest:tt)*) => {
+        $crate::fb_trait! { @attrs [$($attrs)*] [$async_name []] $($rest)* }
+    };
+    (@attrs [$($attrs:tt)*] $config:tt #[fb_trait(async_name = $async_name:ident, blanket_impl)] $($rest:tt)*) => {
+        $crate::fb_trait! { @attrs [$($attrs)*] [$async_name [blanket_impl]] $($rest)* }
+    };
+    (@attrs $attrs:tt $config:tt #[fb_trait($($options:tt)*)] $($rest:tt)*) => {
+        compile_error!(concat!(
+            "invalid `#[fb_trait(", stringify!($($options)*), ")]`, ",
+            "expected `#[fb_trait(async_name = Name)]` or `#[fb_trait(async_name = Name, blanket_impl)]`"
+        ));
+    };
+    (@attrs [$($attrs:tt)*] $config:tt #[$meta:meta] $($rest:tt)*) => {
+        $crate::fb_trait! { @attrs [$($attrs)* #[$meta]] $config $($rest)* }
+    };
+    (@attrs $attrs:tt [] $vis:vis trait $name:ident $($rest:tt)*) => {
+        compile_error!(concat!(
+            "missing `#[fb_trait(async_name = ...)]` on `", stringify!($name),
+            "`: `fb_trait!` needs the name of the async trait"
+        ));
+    };
+    (@attrs $attrs:tt $config:tt $vis:vis trait $name:ident $($rest:tt)*) => {
+        $crate::fb_trait! { @header $attrs $config [$vis $name] [] $($rest)* }
+    };
+
+    // Internal: collects the supertraits up to the trait body.
+    (@header $attrs:tt $config:tt $head:tt $supertraits:tt { $($items:tt)* }) => {
+        $crate::fb_trait! { @items $attrs $config $head $supertraits [] $($items)* }
+    };
+    (@header $attrs:tt $config:tt $head:tt [$($supertraits:tt)*] $token:tt $($rest:tt)*) => {
+        $crate::fb_trait! { @header $attrs $config $head [$($supertraits)* $token] $($rest)* }
+    };
+
+    // Internal: splits the trait body into methods, each ending in `;` or a default body.
+    (@items $attrs:tt $config:tt $head:tt $supertraits:tt [$($methods:tt)*] $(#[$meta:meta])* fn $method:ident ($($params:tt)*) $(-> $return_type:ty)? ; $($rest:tt)*) => {
+        $crate::fb_trait! {
+            @items $attrs $config $head $supertraits
+            [$($methods)* [[$(#[$meta])*] $method ($($params)*) [$(-> $return_type)?] [;]]]
+            $($rest)*
+        }
+    };
+    (@items $attrs:tt $config:tt $head:tt $supertraits:tt [$($methods:tt)*] $(#[$meta:meta])* fn $method:ident ($($params:tt)*) $(-> $return_type:ty)? $body:block $($rest:tt)*) => {
+        $crate::fb_trait! {
+            @items $attrs $config $head $supertraits
+            [$($methods)* [[$(#[$meta])*] $method ($($params)*) [$(-> $return_type)?] [$body]]]
+            $($rest)*
+        }
+    };
+    (@items $attrs:tt $config:tt [$vis:vis $name:ident] $supertraits:tt $methods:tt $($rest:tt)+) => {
+        compile_error!(concat!(
+            "unsupported item in `", stringify!($name), "`: `fb_trait!` expects methods written as ",
+            "`fn name(&self, param: Type, ...) -> ReturnType;` or with a default body"
+        ));
+    };
+    (@items [$($attrs:tt)*] [$async_name:ident [$($blanket_impl:ident)?]] [$vis:vis $name:ident] [$($supertraits:tt)*] [$([[$($meta:tt)*] $method:ident $params:tt [$($return_type:tt)*] [$($body:tt)*]])*]) => {
+        $($attrs)*
+        $vis trait $name $($supertraits)* {
+            $($crate::fb! { $($meta)* trait sync, fn $method $params $($return_type)* $($body)* })*
+        }
+
+        $($attrs)*
+        $vis trait $async_name $($supertraits)* {
+            $($crate::fb! { $($meta)* trait async, fn $method $params $($return_type)* $($body)* })*
+        }
+
+        $crate::fb_trait! { @blanket_impl [$($blanket_impl)?] $name $async_name $([$method $params [$($return_type)*]])* }
+    };
+
+    // Internal: implements the async trait for every implementor of the blocking one.
+    (@blanket_impl [] $($rest:tt)*) => {};
+    (@blanket_impl [blanket_impl] $name:ident $async_name:ident $([$method:ident $params:tt $return_type:tt])*) => {
+        impl<T: $name + ::core::marker::Sync> $async_name for T {
+            $($crate::fb_trait! { @forward $name $method $params $return_type })*
+        }
+    };
+    (@forward $name:ident $method:ident (& mut $self:ident $(, $param:ident : $param_type:ty)* $(,)?) [$(-> $return_type:ty)?]) => {
+        fn $method(&mut $self $(, $param: $param_type)*)
+            -> impl ::core::future::Future<Output = $crate::fb_trait!(@output $($return_type)?)> + ::core::marker::Send {
+            ::core::future::ready($name::$method($self $(, $param)*))
+        }
+    };
+    (@forward $name:ident $method:ident (& $self:ident $(, $param:ident : $param_type:ty)* $(,)?) [$(-> $return_type:ty)?]) => {
+        fn $method(&$self $(, $param: $param_type)*)
+            -> impl ::core::future::Future<Output = $crate::fb_trait!(@output $($return_type)?)> + ::core::marker::Send {
+            ::core::future::ready($name::$method($self $(, $param)*))
+        }
+    };
+    (@forward $name:ident $method:ident ($self:ident $(, $param:ident : $param_type:ty)* $(,)?) [$(-> $return_type:ty)?]) => {
+        fn $method($self $(, $param: $param_type)*)
+            -> impl ::core::future::Future<Output = $crate::fb_trait!(@output $($return_type)?)> + ::core::marker::Send {
+            ::core::future::ready($name::$method($self $(, $param)*))
+        }
+    };
+    (@forward $name:ident $method:ident $params:tt $return_type:tt) => {
+        compile_error!(concat!(
+            "`blanket_impl` can't forward `", stringify!($method), stringify!($params),
+            "`: methods need a `self`, `&self` or `&mut self` receiver followed by `name: Type` parameters"
+        ));
+    };
+    (@output) => { () };
+    (@output $return_type:ty) => { $return_type };
+
+    // Pattern for a trait definition
+    ($($definition:tt)+) => {
+        $crate::fb_trait! { @attrs [] [] $($definition)+ }
+    };
+}
+
+/// The `fb_await!` macro marks an await point inside a body handed to [`fb!`].
+///
+/// Every body generated by `fb!` has its own `fb_await!` in scope, so it doesn't need to be imported: in async functions, closures and blocks `fb_await!(expr)` becomes `expr.await`, while in their sync counterparts it becomes plain `expr`.
+/// This lets one body serve both the `sync` and the `async` arms, for example from a wrapper macro or through the `both` mode.
+///
+/// `fb_await!(sync_expr, async_expr)` goes one step further and picks `sync_expr` in sync bodies and `async_expr.await` in async ones, which is handy when the two flavours call differently named functions.
+///
+/// Used outside of an `fb!` body, the macro fails to compile, since there is no mode to decide whether to await.
+///
+/// # Usage
+///
+/// ```
+/// macro_rules! define_sum {
+///     ($mode:tt) => {
+///         flexi_func_declarative::fb!($mode, pub sum, (values: Vec<u32>), -> u32, {
+///             let mut total = 0;
+///             for value in values {
+///                 total += fb_await!(source::read(value));
+///             }
+///             total
+///         });
+///     };
+/// }
+///
+/// mod blocking {
+///     mod source {
+///         pub fn read(value: u32) -> u32 {
+///             value
+///         }
+///     }
+///
+///     define_sum!(sync);
+/// }
+///
+/// mod nonblocking {
+///     mod source {
+///         pub async fn read(value: u32) -> u32 {
+///             value
+///         }
+///     }
+///
+///     define_sum!(async);
+/// }
+///
+/// assert_eq!(blocking::sum(vec![1, 2, 3]), 6);
+/// assert_eq!(flexi_func_declarative::block_on(nonblocking::sum(vec![1, 2, 3])), 6);
+/// ```
+#[macro_export]
+macro_rules! fb_await {
+    ($($tokens:tt)*) => {
+        compile_error!("`fb_await!` can only be used inside a body generated by `fb!`")
+    };
+}
+
+// Expansions of the `fb_await!` marker, brought into scope inside the bodies generated by `fb!`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __fb_await_sync {
+    ($expr:expr) => {
+        $expr
+    };
+    ($sync_expr:expr, $async_expr:expr) => {
+        $sync_expr
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __fb_await_async {
+    ($expr:expr) => {
+        $expr.await
+    };
+    ($sync_expr:expr, $async_expr:expr) => {
+        $async_expr.await
+    };
+}
+
+// Checks a future against the bounds of `fb!(async(send), ...)` by passing it through a helper
+// named after the generated function, so a failing bound is reported as required by `name`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __fb_send_future {
+    ([$($bounds:tt)+] $name:ident [async $($move:tt)?] { $($body:tt)* }) => {{
+        let future = async $($move)? { $($body)* };
+        {
+            fn $name<F: ::core::future::Future $($bounds)+>(future: F) -> F {
+                future
+            }
+            $name(future)
+        }
+    }};
+}
+
+// Splits a list of definitions at their bodies, which are the first brace-delimited group of
+// each, and hands every definition on to the given macro rule. The attributes and visibility are
+// matched as a whole and the body is looked for among the next 32 tokens at once, so each
+// definition costs a single level of recursion. Each one is emitted next to the split of the
+// ones after it, and longer signatures take one more level for every further 8 tokens.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __fb_split {
+    (@long [$macro:ident $($prefix:tt)*] [$($item:tt)*] { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $($item)* { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (@long [$macro:ident $($prefix:tt)*] [$($item:tt)*] $t1:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $($item)* $t1 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (@long [$macro:ident $($prefix:tt)*] [$($item:tt)*] $t1:tt $t2:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $($item)* $t1 $t2 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (@long [$macro:ident $($prefix:tt)*] [$($item:tt)*] $t1:tt $t2:tt $t3:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $($item)* $t1 $t2 $t3 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (@long [$macro:ident $($prefix:tt)*] [$($item:tt)*] $t1:tt $t2:tt $t3:tt $t4:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $($item)* $t1 $t2 $t3 $t4 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (@long [$macro:ident $($prefix:tt)*] [$($item:tt)*] $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $($item)* $t1 $t2 $t3 $t4 $t5 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (@long [$macro:ident $($prefix:tt)*] [$($item:tt)*] $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $($item)* $t1 $t2 $t3 $t4 $t5 $t6 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (@long [$macro:ident $($prefix:tt)*] [$($item:tt)*] $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $($item)* $t1 $t2 $t3 $t4 $t5 $t6 $t7 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (@long $next:tt [$($item:tt)*] $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $($rest:tt)*) => {
+        $crate::__fb_split! { @long $next [$($item)* $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8] $($rest)* }
+    };
+    ($next:tt) => {};
+    ([$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $(#[$($attr)*])* $vis $keyword { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    ([$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident $t1:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $(#[$($attr)*])* $vis $keyword $t1 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    ([$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident $t1:tt $t2:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $(#[$($attr)*])* $vis $keyword $t1 $t2 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    ([$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident $t1:tt $t2:tt $t3:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $(#[$($attr)*])* $vis $keyword $t1 $t2 $t3 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    ([$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident $t1:tt $t2:tt $t3:tt $t4:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $(#[$($attr)*])* $vis $keyword $t1 $t2 $t3 $t4 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    ([$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $(#[$($attr)*])* $vis $keyword $t1 $t2 $t3 $t4 $t5 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    ([$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $(#[$($attr)*])* $vis $keyword $t1 $t2 $t3 $t4 $t5 $t6 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    ([$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $(#[$($attr)*])* $vis $keyword $t1 $t2 $t3 $t4 $t5 $t6 $t7 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    ([$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt { $($body:tt)* } $($more:tt)*) => {
+        $crate::$macro! { $($prefix)* $(#[$($attr)*])* $vis $keyword $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 { $($body)* } }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt $t20:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19 $t20
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt $t20:tt $t21:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19 $t20 $t21
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt $t20:tt $t21:tt $t22:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19 $t20 $t21 $t22
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt $t20:tt $t21:tt $t22:tt $t23:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19 $t20 $t21 $t22 $t23
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt $t20:tt $t21:tt $t22:tt $t23:tt $t24:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19 $t20 $t21 $t22 $t23 $t24
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt $t20:tt $t21:tt $t22:tt $t23:tt $t24:tt $t25:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19 $t20 $t21 $t22 $t23 $t24 $t25
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt $t20:tt $t21:tt $t22:tt $t23:tt $t24:tt $t25:tt $t26:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19 $t20 $t21 $t22 $t23 $t24 $t25 $t26
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt $t20:tt $t21:tt $t22:tt $t23:tt $t24:tt $t25:tt $t26:tt $t27:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19 $t20 $t21 $t22 $t23 $t24 $t25 $t26 $t27
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt $t20:tt $t21:tt $t22:tt $t23:tt $t24:tt $t25:tt $t26:tt $t27:tt $t28:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19 $t20 $t21 $t22 $t23 $t24 $t25 $t26 $t27 $t28
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt $t20:tt $t21:tt $t22:tt $t23:tt $t24:tt $t25:tt $t26:tt $t27:tt $t28:tt $t29:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19 $t20 $t21 $t22 $t23 $t24 $t25 $t26 $t27 $t28 $t29
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt $t20:tt $t21:tt $t22:tt $t23:tt $t24:tt $t25:tt $t26:tt $t27:tt $t28:tt $t29:tt $t30:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19 $t20 $t21 $t22 $t23 $t24 $t25 $t26 $t27 $t28 $t29 $t30
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        [$macro:ident $($prefix:tt)*] $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt $t20:tt $t21:tt $t22:tt $t23:tt $t24:tt $t25:tt $t26:tt $t27:tt $t28:tt $t29:tt $t30:tt $t31:tt
+        { $($body:tt)* } $($more:tt)*
+    ) => {
+        $crate::$macro! {
+            $($prefix)* $(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19 $t20 $t21 $t22 $t23 $t24 $t25 $t26 $t27 $t28 $t29 $t30 $t31
+            { $($body)* }
+        }
+        $crate::__fb_split! { [$macro $($prefix)*] $($more)* }
+    };
+    (
+        $next:tt $(#[$($attr:tt)*])* $vis:vis $keyword:ident
+        $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $t9:tt $t10:tt $t11:tt $t12:tt $t13:tt $t14:tt $t15:tt $t16:tt
+        $t17:tt $t18:tt $t19:tt $t20:tt $t21:tt $t22:tt $t23:tt $t24:tt $t25:tt $t26:tt $t27:tt $t28:tt $t29:tt $t30:tt $t31:tt $t32:tt
+        $($rest:tt)*
+    ) => {
+        $crate::__fb_split! {
+            @long $next [$(#[$($attr)*])* $vis $keyword
+            $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8 $t9 $t10 $t11 $t12 $t13 $t14 $t15 $t16
+            $t17 $t18 $t19 $t20 $t21 $t22 $t23 $t24 $t25 $t26 $t27 $t28 $t29 $t30 $t31 $t32]
+            $($rest)*
+        }
     };
 }
@@ -0,0 +1,137 @@
+mod common;
+
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use flexi_func_declarative::{block_on, fb};
+
+fb!(async_boxed, pub greet, (name: &str), -> String, {
+    common::yield_now().await;
+    format!("Hello, {}", name)
+});
+
+fb!(async_boxed, fn longest(a: &str, b: &str) -> usize {
+    a.len().max(b.len())
+});
+
+fb!(async_boxed, fn total<T>(values: &[&T], extra: (&T, u32)) -> u32 where T: Copy + Into<u32> + Sync {
+    values.iter().map(|value| (**value).into()).sum::<u32>() + (*extra.0).into() + extra.1
+});
+
+fb!(async_boxed, fn first<'a>(items: &'a [String], fallback: &'a str) -> &'a str {
+    items.first().map_or(fallback, String::as_str)
+});
+
+fb!(async_boxed, error = std::num::ParseIntError, fn parse(text: &str) -> u32 {
+    text.trim().parse::<u32>()?
+});
+
+fb!(async_boxed(?Send), fn shared(value: Rc<u32>) -> u32 {
+    common::yield_now().await;
+    *value
+});
+
+fb!(async_boxed, fn show(first: impl Display + Send, rest: &[impl Display + Sync], last: &mut (impl Display + Send)) -> String {
+    format!("{}{}{}", first, rest.len(), last)
+});
+
+fb!(async_boxed(?Send), fn show_local(first: &impl Display, last: &'static mut impl Display) -> String {
+    format!("{}{}", first, last)
+});
+
+fb!(async_boxed, fn log(lines: &mut Vec<String>, line: String) {
+    lines.push(line);
+});
+
+trait Store {
+    fb!(async_boxed, fn get(&self, key: &str) -> Option<u32>;);
+}
+
+struct Fixed(u32);
+
+impl Store for Fixed {
+    fb!(async_boxed, fn get(&self, key: &str) -> Option<u32> {
+        (key == "answer").then_some(self.0)
+    });
+}
+
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fb!(async_boxed, fn bump(&mut self, by: u32) -> u32 {
+        self.count += by;
+        self.count
+    });
+
+    fb!(async_boxed, fn pinned(self: Pin<&mut Self>, by: &u32) -> u32 {
+        self.count + by
+    });
+
+    fb!(async_boxed, fn boxed(mut self: Box<Self>) -> u32 {
+        self.count += 1;
+        self.count
+    });
+}
+
+struct View<'a> {
+    text: &'a str,
+}
+
+impl<'a> View<'a> {
+    fb!(async_boxed, fn into_len(self) -> usize {
+        self.text.len()
+    });
+
+    fb!(async_boxed, fn boxed_len(mut self: Box<Self>, suffix: &str) -> usize {
+        self.text = suffix;
+        self.text.len()
+    });
+
+    fb!(async_boxed, fn pinned_len(self: Pin<&mut Self>) -> usize {
+        self.text.len()
+    });
+}
+
+fn assert_send<F: Future + Send>(future: F) -> F {
+    future
+}
+
+#[test]
+fn returns_boxed_futures() {
+    let future: Pin<Box<dyn Future<Output = String> + Send + '_>> = greet("ferris");
+    assert_eq!(block_on(future), "Hello, ferris");
+    assert_eq!(block_on(assert_send(longest("ab", "abc"))), 3);
+    assert_eq!(block_on(total(&[&1u8, &2u8], (&3u8, 4))), 10);
+    assert_eq!(block_on(first(&[], "none")), "none");
+    assert_eq!(block_on(parse(" 7 ")), Ok(7));
+    assert!(block_on(parse("x")).is_err());
+    assert_eq!(block_on(shared(Rc::new(5))), 5);
+    assert_eq!(block_on(show(1, &[2, 3], &mut 4)), "124");
+    assert_eq!(block_on(show_local(&1, Box::leak(Box::new(2)))), "12");
+
+    let mut lines = Vec::new();
+    block_on(log(&mut lines, "done".to_string()));
+    assert_eq!(lines, ["done"]);
+}
+
+#[test]
+fn works_in_dyn_compatible_traits() {
+    let stores: Vec<Box<dyn Store>> = vec![Box::new(Fixed(42))];
+    assert_eq!(block_on(stores[0].get("answer")), Some(42));
+    assert_eq!(block_on(stores[0].get("other")), None);
+
+    let mut counter = Counter { count: 1 };
+    assert_eq!(block_on(counter.bump(2)), 3);
+    assert_eq!(block_on(Pin::new(&mut counter).pinned(&1)), 4);
+    assert_eq!(block_on(Box::new(counter).boxed()), 4);
+
+    let text = String::from("borrowed");
+    assert_eq!(block_on(View { text: &text }.into_len()), 8);
+    assert_eq!(block_on(Box::new(View { text: &text }).boxed_len("abc")), 3);
+    let mut view = View { text: &text };
+    assert_eq!(block_on(Pin::new(&mut view).pinned_len()), 8);
+}
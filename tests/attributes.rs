@@ -0,0 +1,68 @@
+mod common;
+
+use flexi_func_declarative::fb;
+
+fb!(
+    /// Adds one to its input.
+    #[inline]
+    #[must_use]
+    sync, pub increment, (x: u32), -> u32, {
+        x + 1
+    }
+);
+
+fb!(
+    /// Asynchronously adds two to its input.
+    #[inline]
+    async, pub increment_twice, (x: u32), -> u32, {
+        x + 2
+    }
+);
+
+fb!(#[cfg(any())] sync, platform, (), -> &'static str, {
+    "disabled"
+});
+
+fb!(#[cfg(all())] sync, platform, (), -> &'static str, {
+    "enabled"
+});
+
+fb!(#[deprecated(note = "use `increment` instead")] sync, old_increment, (x: u32), -> u32, {
+    x + 1
+});
+
+fb!(#[allow(clippy::needless_lifetimes)] async, echo<'a>, (s: &'a str), -> &'a str, {
+    s
+});
+
+#[test]
+fn function_attributes_are_forwarded() {
+    assert_eq!(increment(1), 2);
+    assert_eq!(common::block_on(increment_twice(1)), 3);
+    assert_eq!(common::block_on(echo("hi")), "hi");
+}
+
+#[test]
+fn cfg_attributes_select_the_function() {
+    assert_eq!(platform(), "enabled");
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_functions_remain_callable() {
+    assert_eq!(old_increment(1), 2);
+}
+
+#[test]
+fn closure_attributes_are_forwarded() {
+    let sync_closure = fb!(#[allow(unused_variables)] sync, closure, {
+        let unused = 1;
+        5
+    });
+    let async_closure = fb!(#[allow(unused_variables)] async, closure, {
+        let unused = 1;
+        6
+    });
+    assert_eq!(sync_closure(), 5);
+    assert_eq!(common::block_on(async_closure()), 6);
+}
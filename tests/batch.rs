@@ -0,0 +1,473 @@
+mod common;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use flexi_func_declarative::{block_on, fb};
+
+fb! {
+    async {
+        /// Looks up a user name.
+        pub fn user_name(id: u64) -> String {
+            common::yield_now().await;
+            format!("user-{}", id)
+        }
+
+        pub(crate) fn user_names(ids: Vec<u64>) -> Vec<String> {
+            let mut names = Vec::new();
+            for id in ids {
+                names.push(user_name(id).await);
+            }
+            names
+        }
+
+        fn touch(log: &mut Vec<u64>, id: u64) {
+            log.push(id);
+        }
+
+        fn describe<T>(value: T) -> String
+        where
+            T: Display,
+        {
+            fb_await!(std::future::ready(value.to_string()))
+        }
+    }
+}
+
+fb! {
+    #[inline]
+    sync {
+        fn add(a: u32, b: u32) -> u32 {
+            a + b
+        }
+
+        #[must_use]
+        fn largest<T: PartialOrd + Copy>(values: &[T]) -> Option<T> {
+            let mut largest = None;
+            for &value in values {
+                if largest.is_none_or(|current| value > current) {
+                    largest = Some(value);
+                }
+            }
+            largest
+        }
+    }
+}
+
+// `cfg(test)` holds for integration tests, so these are the async variants.
+fb! {
+    cfg(test) {
+        fn configured(value: u32) -> u32 {
+            fb_await!(std::future::ready(value))
+        }
+    }
+}
+
+fb! {
+    sync {}
+}
+
+// Forty definitions with generics, `where` clauses and patterns, in the shapes of a real
+// service module. Each one adds a single level of recursion.
+mod service {
+    use std::collections::HashMap;
+    use std::fmt::Display;
+    use std::hash::Hash;
+    use std::num::ParseIntError;
+
+    use flexi_func_declarative::fb;
+
+    use super::common;
+
+    fb! {
+        async {
+            /// Appends `extra` to `buf` and reports its length past `offset`, or `label` when it's too short.
+            pub fn read_0<T: Into<Vec<u8>> + Clone, L>(mut buf: Vec<u8>, (offset, len): (usize, usize), extra: T, label: L) -> Result<usize, String>
+            where
+                L: std::fmt::Debug,
+            {
+                buf.extend(extra.clone().into());
+                if offset > buf.len() {
+                    return Err(format!("{:?} ends before {}", label, offset));
+                }
+                Ok(buf.len() - offset + len)
+            }
+
+            pub(crate) fn lookup_1<'a, K, V>(map: &'a HashMap<K, V>, key: &K) -> Option<&'a V>
+            where
+                K: Eq + Hash,
+                V: Clone,
+            {
+                common::yield_now().await;
+                map.get(key)
+            }
+
+            #[must_use]
+            pub fn sum_2<I>(items: I, [first, second]: [u32; 2]) -> u32
+            where
+                I: IntoIterator<Item = u32>,
+                I::IntoIter: Send,
+            {
+                items.into_iter().sum::<u32>() + first * second + 2
+            }
+
+            pub fn join_3<T: Display, const M: usize>(values: [T; M], sep: &str) -> String {
+                let parts: Vec<String> = values.iter().map(ToString::to_string).collect();
+                format!("{}{}3", parts.join(sep), sep)
+            }
+
+            pub fn fetch_4(log: &mut Vec<String>, url: &str, retries: u8) -> Result<usize, ParseIntError> {
+                log.push(url.to_owned());
+                let code: usize = fb_await!(std::future::ready(url.len().to_string())).parse()?;
+                Ok(code + retries as usize + 4)
+            }
+
+            /// Appends `extra` to `buf` and reports its length past `offset`, or `label` when it's too short.
+            pub fn read_5<T: Into<Vec<u8>> + Clone, L>(mut buf: Vec<u8>, (offset, len): (usize, usize), extra: T, label: L) -> Result<usize, String>
+            where
+                L: std::fmt::Debug,
+            {
+                buf.extend(extra.clone().into());
+                if offset > buf.len() {
+                    return Err(format!("{:?} ends before {}", label, offset));
+                }
+                Ok(buf.len() - offset + len + 5)
+            }
+
+            pub(crate) fn lookup_6<'a, K, V>(map: &'a HashMap<K, V>, key: &K) -> Option<&'a V>
+            where
+                K: Eq + Hash,
+                V: Clone,
+            {
+                common::yield_now().await;
+                map.get(key)
+            }
+
+            #[must_use]
+            pub fn sum_7<I>(items: I, [first, second]: [u32; 2]) -> u32
+            where
+                I: IntoIterator<Item = u32>,
+                I::IntoIter: Send,
+            {
+                items.into_iter().sum::<u32>() + first * second + 7
+            }
+
+            pub fn join_8<T: Display, const M: usize>(values: [T; M], sep: &str) -> String {
+                let parts: Vec<String> = values.iter().map(ToString::to_string).collect();
+                format!("{}{}8", parts.join(sep), sep)
+            }
+
+            pub fn fetch_9(log: &mut Vec<String>, url: &str, retries: u8) -> Result<usize, ParseIntError> {
+                log.push(url.to_owned());
+                let code: usize = fb_await!(std::future::ready(url.len().to_string())).parse()?;
+                Ok(code + retries as usize + 9)
+            }
+
+            /// Appends `extra` to `buf` and reports its length past `offset`, or `label` when it's too short.
+            pub fn read_10<T: Into<Vec<u8>> + Clone, L>(mut buf: Vec<u8>, (offset, len): (usize, usize), extra: T, label: L) -> Result<usize, String>
+            where
+                L: std::fmt::Debug,
+            {
+                buf.extend(extra.clone().into());
+                if offset > buf.len() {
+                    return Err(format!("{:?} ends before {}", label, offset));
+                }
+                Ok(buf.len() - offset + len + 10)
+            }
+
+            pub(crate) fn lookup_11<'a, K, V>(map: &'a HashMap<K, V>, key: &K) -> Option<&'a V>
+            where
+                K: Eq + Hash,
+                V: Clone,
+            {
+                common::yield_now().await;
+                map.get(key)
+            }
+
+            #[must_use]
+            pub fn sum_12<I>(items: I, [first, second]: [u32; 2]) -> u32
+            where
+                I: IntoIterator<Item = u32>,
+                I::IntoIter: Send,
+            {
+                items.into_iter().sum::<u32>() + first * second + 12
+            }
+
+            pub fn join_13<T: Display, const M: usize>(values: [T; M], sep: &str) -> String {
+                let parts: Vec<String> = values.iter().map(ToString::to_string).collect();
+                format!("{}{}13", parts.join(sep), sep)
+            }
+
+            pub fn fetch_14(log: &mut Vec<String>, url: &str, retries: u8) -> Result<usize, ParseIntError> {
+                log.push(url.to_owned());
+                let code: usize = fb_await!(std::future::ready(url.len().to_string())).parse()?;
+                Ok(code + retries as usize + 14)
+            }
+
+            /// Appends `extra` to `buf` and reports its length past `offset`, or `label` when it's too short.
+            pub fn read_15<T: Into<Vec<u8>> + Clone, L>(mut buf: Vec<u8>, (offset, len): (usize, usize), extra: T, label: L) -> Result<usize, String>
+            where
+                L: std::fmt::Debug,
+            {
+                buf.extend(extra.clone().into());
+                if offset > buf.len() {
+                    return Err(format!("{:?} ends before {}", label, offset));
+                }
+                Ok(buf.len() - offset + len + 15)
+            }
+
+            pub(crate) fn lookup_16<'a, K, V>(map: &'a HashMap<K, V>, key: &K) -> Option<&'a V>
+            where
+                K: Eq + Hash,
+                V: Clone,
+            {
+                common::yield_now().await;
+                map.get(key)
+            }
+
+            #[must_use]
+            pub fn sum_17<I>(items: I, [first, second]: [u32; 2]) -> u32
+            where
+                I: IntoIterator<Item = u32>,
+                I::IntoIter: Send,
+            {
+                items.into_iter().sum::<u32>() + first * second + 17
+            }
+
+            pub fn join_18<T: Display, const M: usize>(values: [T; M], sep: &str) -> String {
+                let parts: Vec<String> = values.iter().map(ToString::to_string).collect();
+                format!("{}{}18", parts.join(sep), sep)
+            }
+
+            pub fn fetch_19(log: &mut Vec<String>, url: &str, retries: u8) -> Result<usize, ParseIntError> {
+                log.push(url.to_owned());
+                let code: usize = fb_await!(std::future::ready(url.len().to_string())).parse()?;
+                Ok(code + retries as usize + 19)
+            }
+
+            /// Appends `extra` to `buf` and reports its length past `offset`, or `label` when it's too short.
+            pub fn read_20<T: Into<Vec<u8>> + Clone, L>(mut buf: Vec<u8>, (offset, len): (usize, usize), extra: T, label: L) -> Result<usize, String>
+            where
+                L: std::fmt::Debug,
+            {
+                buf.extend(extra.clone().into());
+                if offset > buf.len() {
+                    return Err(format!("{:?} ends before {}", label, offset));
+                }
+                Ok(buf.len() - offset + len + 20)
+            }
+
+            pub(crate) fn lookup_21<'a, K, V>(map: &'a HashMap<K, V>, key: &K) -> Option<&'a V>
+            where
+                K: Eq + Hash,
+                V: Clone,
+            {
+                common::yield_now().await;
+                map.get(key)
+            }
+
+            #[must_use]
+            pub fn sum_22<I>(items: I, [first, second]: [u32; 2]) -> u32
+            where
+                I: IntoIterator<Item = u32>,
+                I::IntoIter: Send,
+            {
+                items.into_iter().sum::<u32>() + first * second + 22
+            }
+
+            pub fn join_23<T: Display, const M: usize>(values: [T; M], sep: &str) -> String {
+                let parts: Vec<String> = values.iter().map(ToString::to_string).collect();
+                format!("{}{}23", parts.join(sep), sep)
+            }
+
+            pub fn fetch_24(log: &mut Vec<String>, url: &str, retries: u8) -> Result<usize, ParseIntError> {
+                log.push(url.to_owned());
+                let code: usize = fb_await!(std::future::ready(url.len().to_string())).parse()?;
+                Ok(code + retries as usize + 24)
+            }
+
+            /// Appends `extra` to `buf` and reports its length past `offset`, or `label` when it's too short.
+            pub fn read_25<T: Into<Vec<u8>> + Clone, L>(mut buf: Vec<u8>, (offset, len): (usize, usize), extra: T, label: L) -> Result<usize, String>
+            where
+                L: std::fmt::Debug,
+            {
+                buf.extend(extra.clone().into());
+                if offset > buf.len() {
+                    return Err(format!("{:?} ends before {}", label, offset));
+                }
+                Ok(buf.len() - offset + len + 25)
+            }
+
+            pub(crate) fn lookup_26<'a, K, V>(map: &'a HashMap<K, V>, key: &K) -> Option<&'a V>
+            where
+                K: Eq + Hash,
+                V: Clone,
+            {
+                common::yield_now().await;
+                map.get(key)
+            }
+
+            #[must_use]
+            pub fn sum_27<I>(items: I, [first, second]: [u32; 2]) -> u32
+            where
+                I: IntoIterator<Item = u32>,
+                I::IntoIter: Send,
+            {
+                items.into_iter().sum::<u32>() + first * second + 27
+            }
+
+            pub fn join_28<T: Display, const M: usize>(values: [T; M], sep: &str) -> String {
+                let parts: Vec<String> = values.iter().map(ToString::to_string).collect();
+                format!("{}{}28", parts.join(sep), sep)
+            }
+
+            pub fn fetch_29(log: &mut Vec<String>, url: &str, retries: u8) -> Result<usize, ParseIntError> {
+                log.push(url.to_owned());
+                let code: usize = fb_await!(std::future::ready(url.len().to_string())).parse()?;
+                Ok(code + retries as usize + 29)
+            }
+
+            /// Appends `extra` to `buf` and reports its length past `offset`, or `label` when it's too short.
+            pub fn read_30<T: Into<Vec<u8>> + Clone, L>(mut buf: Vec<u8>, (offset, len): (usize, usize), extra: T, label: L) -> Result<usize, String>
+            where
+                L: std::fmt::Debug,
+            {
+                buf.extend(extra.clone().into());
+                if offset > buf.len() {
+                    return Err(format!("{:?} ends before {}", label, offset));
+                }
+                Ok(buf.len() - offset + len + 30)
+            }
+
+            pub(crate) fn lookup_31<'a, K, V>(map: &'a HashMap<K, V>, key: &K) -> Option<&'a V>
+            where
+                K: Eq + Hash,
+                V: Clone,
+            {
+                common::yield_now().await;
+                map.get(key)
+            }
+
+            #[must_use]
+            pub fn sum_32<I>(items: I, [first, second]: [u32; 2]) -> u32
+            where
+                I: IntoIterator<Item = u32>,
+                I::IntoIter: Send,
+            {
+                items.into_iter().sum::<u32>() + first * second + 32
+            }
+
+            pub fn join_33<T: Display, const M: usize>(values: [T; M], sep: &str) -> String {
+                let parts: Vec<String> = values.iter().map(ToString::to_string).collect();
+                format!("{}{}33", parts.join(sep), sep)
+            }
+
+            pub fn fetch_34(log: &mut Vec<String>, url: &str, retries: u8) -> Result<usize, ParseIntError> {
+                log.push(url.to_owned());
+                let code: usize = fb_await!(std::future::ready(url.len().to_string())).parse()?;
+                Ok(code + retries as usize + 34)
+            }
+
+            /// Appends `extra` to `buf` and reports its length past `offset`, or `label` when it's too short.
+            pub fn read_35<T: Into<Vec<u8>> + Clone, L>(mut buf: Vec<u8>, (offset, len): (usize, usize), extra: T, label: L) -> Result<usize, String>
+            where
+                L: std::fmt::Debug,
+            {
+                buf.extend(extra.clone().into());
+                if offset > buf.len() {
+                    return Err(format!("{:?} ends before {}", label, offset));
+                }
+                Ok(buf.len() - offset + len + 35)
+            }
+
+            pub(crate) fn lookup_36<'a, K, V>(map: &'a HashMap<K, V>, key: &K) -> Option<&'a V>
+            where
+                K: Eq + Hash,
+                V: Clone,
+            {
+                common::yield_now().await;
+                map.get(key)
+            }
+
+            #[must_use]
+            pub fn sum_37<I>(items: I, [first, second]: [u32; 2]) -> u32
+            where
+                I: IntoIterator<Item = u32>,
+                I::IntoIter: Send,
+            {
+                items.into_iter().sum::<u32>() + first * second + 37
+            }
+
+            pub fn join_38<T: Display, const M: usize>(values: [T; M], sep: &str) -> String {
+                let parts: Vec<String> = values.iter().map(ToString::to_string).collect();
+                format!("{}{}38", parts.join(sep), sep)
+            }
+
+            pub fn fetch_39(log: &mut Vec<String>, url: &str, retries: u8) -> Result<usize, ParseIntError> {
+                log.push(url.to_owned());
+                let code: usize = fb_await!(std::future::ready(url.len().to_string())).parse()?;
+                Ok(code + retries as usize + 39)
+            }
+        }
+    }
+}
+
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fb! {
+        sync {
+            fn get(&self) -> u32 {
+                self.count
+            }
+
+            fn bump(&mut self, by: u32) {
+                self.count += by;
+            }
+        }
+    }
+}
+
+#[test]
+fn declares_async_functions() {
+    assert_eq!(block_on(user_name(1)), "user-1");
+    assert_eq!(block_on(user_names(vec![1, 2])), ["user-1", "user-2"]);
+    let mut log = Vec::new();
+    block_on(touch(&mut log, 3));
+    assert_eq!(log, [3]);
+    assert_eq!(block_on(describe(4)), "4");
+}
+
+#[test]
+fn declares_sync_functions_with_shared_attributes() {
+    assert_eq!(add(1, 2), 3);
+    assert_eq!(largest(&[3, 9, 2]), Some(9));
+    assert_eq!(largest::<u8>(&[]), None);
+}
+
+#[test]
+fn declares_cfg_functions() {
+    assert_eq!(block_on(configured(5)), 5);
+}
+
+#[test]
+fn declares_large_batches_of_generic_functions() {
+    assert_eq!(block_on(service::read_0(vec![1], (1, 2), vec![2, 3], "first")), Ok(4));
+    assert_eq!(block_on(service::read_35(Vec::new(), (2, 0), [1u8], "last")), Err("\"last\" ends before 2".to_string()));
+    let map = HashMap::from([("key", 7)]);
+    assert_eq!(block_on(service::lookup_36(&map, &"key")), Some(&7));
+    assert_eq!(block_on(service::sum_37(vec![1, 2], [3, 4])), 52);
+    assert_eq!(block_on(service::join_38([1, 2], "-")), "1-2-38");
+    let mut log = Vec::new();
+    assert_eq!(block_on(service::fetch_39(&mut log, "ab", 1)), Ok(42));
+    assert_eq!(log, ["ab"]);
+}
+
+#[test]
+fn declares_methods() {
+    let mut counter = Counter { count: 1 };
+    counter.bump(2);
+    assert_eq!(counter.get(), 3);
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+use std::thread;
+use std::time::Duration;
+
+use flexi_func_declarative::{block_on, fb};
+
+fb!(async, slow_double, (x: u32), -> u32, {
+    common::yield_now().await;
+    x * 2
+});
+
+#[test]
+fn drives_ready_and_pending_futures() {
+    assert_eq!(block_on(async { 1 }), 1);
+    assert_eq!(block_on(slow_double(21)), 42);
+}
+
+#[test]
+fn wakes_up_when_woken_from_another_thread() {
+    let slot: Arc<Mutex<(Option<u32>, Option<Waker>)>> = Arc::default();
+    let producer = Arc::clone(&slot);
+    let (started_tx, started_rx) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        started_rx.recv().unwrap();
+        thread::sleep(Duration::from_millis(20));
+        let mut guard = producer.lock().unwrap();
+        guard.0 = Some(7);
+        if let Some(waker) = guard.1.take() {
+            waker.wake();
+        }
+    });
+
+    let value = block_on(std::future::poll_fn(|cx| {
+        let mut guard = slot.lock().unwrap();
+        match guard.0 {
+            Some(value) => Poll::Ready(value),
+            None => {
+                if guard.1.replace(cx.waker().clone()).is_none() {
+                    started_tx.send(()).unwrap();
+                }
+                Poll::Pending
+            }
+        }
+    }));
+    handle.join().unwrap();
+    assert_eq!(value, 7);
+}
+
+#[test]
+fn block_on_execute_arm_runs_immediately() {
+    let value = fb!(block_on, execute, {
+        let doubled = slow_double(2).await;
+        doubled + fb_await!(slow_double(1))
+    });
+    assert_eq!(value, 6);
+}
+
+#[test]
+fn block_on_execute_capture_modes() {
+    let mut log = Vec::new();
+    fb!(block_on, ref execute, {
+        common::yield_now().await;
+        log.push("borrowed");
+    });
+    assert_eq!(log, ["borrowed"]);
+
+    let owned = String::from("moved");
+    let length = fb!(block_on, move execute, { owned.len() });
+    assert_eq!(length, 5);
+}
+
+fb!(sync, sync_wrapper, (x: u32), -> u32, {
+    block_on(slow_double(x))
+});
+
+#[test]
+fn sync_code_can_call_async_fb_functions() {
+    assert_eq!(sync_wrapper(5), 10);
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use std::str::FromStr;
+
+use flexi_func_declarative::fb;
+
+fb!(both, pub [word_count, word_count_async], (text: &str), -> usize, {
+    text.split_whitespace().count()
+});
+
+fb!(both, [total_words, total_words_async], (texts: &[&str]), -> usize, {
+    let mut total = 0;
+    for text in texts {
+        total += fb_await!(word_count(text), word_count_async(text));
+    }
+    total
+});
+
+fb!(both, [echo, echo_async], (value: u32), -> u32, {
+    fb_await!(value, std::future::ready(value + 1))
+});
+
+fb!(
+    /// Parses a number, in both flavours.
+    #[inline]
+    both, [parse, parse_async]<T>, (s: &str), -> Result<T, T::Err>, where T: FromStr, {
+        s.trim().parse()
+    }
+);
+
+fb!(both, [remember, remember_async], (log: &mut Vec<String>, line: &str), {
+    log.push(line.to_owned());
+});
+
+struct Store {
+    items: Vec<u32>,
+}
+
+impl Store {
+    fb!(both, pub [push, push_async], (&mut self, item: u32), -> usize, {
+        self.items.push(item);
+        self.items.len()
+    });
+}
+
+#[test]
+fn generates_both_functions() {
+    assert_eq!(word_count("a b c"), 3);
+    assert_eq!(common::block_on(word_count_async("a b c")), 3);
+}
+
+#[test]
+fn marked_await_points_only_apply_to_the_async_copy() {
+    let texts = ["one two", "three"];
+    assert_eq!(total_words(&texts), 3);
+    assert_eq!(common::block_on(total_words_async(&texts)), 3);
+}
+
+#[test]
+fn each_copy_picks_its_own_expression() {
+    assert_eq!(echo(1), 1);
+    assert_eq!(common::block_on(echo_async(1)), 2);
+}
+
+#[test]
+fn shares_attributes_generics_and_where_clauses() {
+    assert_eq!(parse::<u8>(" 4 "), Ok(4));
+    assert_eq!(common::block_on(parse_async::<i16>("-4")), Ok(-4));
+}
+
+#[test]
+fn unit_functions_and_methods() {
+    let mut log = vec![];
+    remember(&mut log, "sync");
+    common::block_on(remember_async(&mut log, "async"));
+    assert_eq!(log, ["sync", "async"]);
+
+    let mut store = Store { items: vec![] };
+    assert_eq!(store.push(1), 1);
+    assert_eq!(common::block_on(store.push_async(2)), 2);
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use std::sync::mpsc;
+use std::thread;
+
+use flexi_func_declarative::fb;
+
+#[test]
+fn sync_move_closure_can_be_sent_to_a_thread() {
+    let (tx, rx) = mpsc::channel();
+    let label = String::from("worker");
+    let job = fb!(sync, move closure, {
+        tx.send(format!("{} done", label)).unwrap();
+    });
+    thread::spawn(job).join().unwrap();
+    assert_eq!(rx.recv().unwrap(), "worker done");
+}
+
+#[test]
+fn sync_move_closure_with_parameters() {
+    let offset = 10;
+    let add = fb!(sync, move closure, |x: u32| -> u32 { x + offset });
+    let handle = thread::spawn(move || add(5));
+    assert_eq!(handle.join().unwrap(), 15);
+}
+
+#[test]
+fn sync_ref_closure_borrows() {
+    let mut hits = Vec::new();
+    let mut record = fb!(sync, ref closure, |hit: u8| { hits.push(hit) });
+    record(1);
+    record(2);
+    assert_eq!(hits, [1, 2]);
+}
+
+#[test]
+fn async_move_closure_owns_its_captures() {
+    let prefix = String::from("id");
+    let make = fb!(async, move closure, |n: u32| {
+        common::yield_now().await;
+        format!("{}-{}", prefix, n)
+    });
+    let handle = thread::spawn(move || common::block_on(make(3)));
+    assert_eq!(handle.join().unwrap(), "id-3");
+}
+
+#[test]
+fn async_ref_closure_borrows() {
+    let names = vec!["a", "b"];
+    let count = fb!(async, ref closure, { names.len() });
+    assert_eq!(common::block_on(count()), 2);
+    assert_eq!(names, ["a", "b"]);
+}
+
+#[test]
+fn async_ref_closure_owns_its_parameters() {
+    let ctx = 1;
+    let names = vec!["a", "b"];
+    let score = fb!(async, ref closure, |x: u32, (skip, extra): (usize, u32)| -> u32 {
+        common::yield_now().await;
+        x + ctx + names.iter().skip(skip).count() as u32 + extra
+    });
+    assert_eq!(common::block_on(score(1, (0, 0))), 4);
+    assert_eq!(common::block_on(score(2, (1, 3))), 7);
+    assert_eq!(names, ["a", "b"]);
+}
+
+#[test]
+fn async_ref_execute_borrows() {
+    let mut log = Vec::new();
+    let future = fb!(async, ref execute, {
+        common::yield_now().await;
+        log.push("scoped");
+    });
+    common::block_on(future);
+    assert_eq!(log, ["scoped"]);
+}
+
+#[test]
+fn async_move_execute_owns_its_captures() {
+    let data = String::from("owned");
+    let future = fb!(async, move execute, { data.len() });
+    let handle = thread::spawn(move || common::block_on(future));
+    assert_eq!(handle.join().unwrap(), 5);
+}
+
+#[test]
+fn sync_execute_capture_modes() {
+    let mut total = 0;
+    fb!(sync, ref execute, { total += 1; });
+    assert_eq!(total, 1);
+
+    let owned = String::from("moved");
+    let length = fb!(sync, move execute, { owned.len() });
+    assert_eq!(length, 5);
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use flexi_func_declarative::{block_on, fb};
+
+mod source {
+    #[allow(dead_code)]
+    pub fn read(value: u32) -> u32 {
+        value
+    }
+
+    pub mod r#async {
+        pub async fn read(value: u32) -> u32 {
+            crate::common::yield_now().await;
+            value
+        }
+    }
+}
+
+// `cfg(test)` holds for integration tests, so this is the async variant.
+fb!(cfg(test), pub load, (value: u32), -> u32, {
+    fb_await!(source::read(value), source::r#async::read(value)) + 1
+});
+
+// `cfg(any())` never holds, so this is the sync variant.
+fb!(cfg(any()), pub store, (value: u32), -> u32, {
+    fb_await!(source::read(value), source::r#async::read(value)) * 2
+});
+
+fb!(
+    /// Documented on both variants.
+    #[inline]
+    cfg(all(test, not(any()))), pub(crate) parse<T>, (s: &str), -> Option<T>, where T: std::str::FromStr, {
+        s.parse().ok()
+    }
+);
+
+struct Counter(u32);
+
+impl Counter {
+    fb!(cfg(any()), bump, (&mut self), {
+        self.0 += 1;
+    });
+}
+
+#[test]
+fn predicate_that_holds_selects_the_async_variant() {
+    assert_eq!(block_on(load(1)), 2);
+    assert_eq!(block_on(parse::<u8>("9")), Some(9));
+}
+
+#[test]
+fn predicate_that_fails_selects_the_sync_variant() {
+    assert_eq!(store(2), 4);
+    let mut counter = Counter(0);
+    counter.bump();
+    assert_eq!(counter.0, 1);
+}
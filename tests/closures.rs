@@ -0,0 +1,80 @@
+mod common;
+
+use flexi_func_declarative::fb;
+
+fn retry<F: FnMut(u32) -> Result<u32, String>>(mut attempt: F) -> Result<u32, String> {
+    let mut last = Err("never ran".to_string());
+    for n in 0..3 {
+        last = attempt(n);
+        if last.is_ok() {
+            break;
+        }
+    }
+    last
+}
+
+#[test]
+fn sync_closures_take_typed_and_untyped_parameters() {
+    let add = fb!(sync, closure, |a: u32, b| { a + b });
+    assert_eq!(add(1, 2), 3);
+
+    let doubled: Vec<u32> = [1, 2, 3].iter().map(fb!(sync, closure, |&x| { x * 2 })).collect();
+    assert_eq!(doubled, [2, 4, 6]);
+
+    let swap = fb!(sync, closure, |(a, b): (u8, u8)| { (b, a) });
+    assert_eq!(swap((1, 2)), (2, 1));
+}
+
+#[test]
+fn sync_closures_with_return_type() {
+    let parse = fb!(sync, closure, |s: &str| -> Result<u32, std::num::ParseIntError> {
+        let value = s.parse::<u32>()?;
+        Ok(value + 1)
+    });
+    assert_eq!(parse("41"), Ok(42));
+    assert!(parse("x").is_err());
+
+    let constant = fb!(sync, closure, || -> u8 { 7 });
+    assert_eq!(constant(), 7);
+}
+
+#[test]
+fn sync_closures_as_callbacks() {
+    let hook = fb!(sync, closure, |n: u32| -> Result<u32, String> {
+        if n < 2 { Err(format!("attempt {} failed", n)) } else { Ok(n) }
+    });
+    assert_eq!(retry(hook), Ok(2));
+}
+
+#[test]
+fn async_closures_take_parameters() {
+    let greet = fb!(async, closure, |id: u64, name| {
+        common::yield_now().await;
+        format!("{}:{}", id, name)
+    });
+    assert_eq!(common::block_on(greet(7, "ferris")), "7:ferris");
+
+    let futures: Vec<_> = [1u32, 2].into_iter().map(fb!(async, closure, |x| { x + 10 })).collect();
+    let results: Vec<u32> = futures.into_iter().map(common::block_on).collect();
+    assert_eq!(results, [11, 12]);
+}
+
+#[test]
+fn async_closures_with_return_type() {
+    let parse = fb!(async, closure, |s: String| -> Result<u32, std::num::ParseIntError> {
+        common::yield_now().await;
+        let value = s.parse::<u32>()?;
+        Ok(value * 2)
+    });
+    assert_eq!(common::block_on(parse("21".to_string())), Ok(42));
+
+    let constant = fb!(async, closure, || -> &'static str { "done" });
+    assert_eq!(common::block_on(constant()), "done");
+}
+
+#[test]
+fn closures_without_parameters_still_work() {
+    let sync_closure = fb!(sync, closure, { 1 });
+    let async_closure = fb!(async, closure, { 2 });
+    assert_eq!(sync_closure() + common::block_on(async_closure()), 3);
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+use std::num::ParseIntError;
+
+use flexi_func_declarative::{block_on, fb};
+
+#[derive(Debug, PartialEq)]
+enum AppError {
+    Parse(String),
+    Empty,
+}
+
+impl From<ParseIntError> for AppError {
+    fn from(error: ParseIntError) -> Self {
+        AppError::Parse(error.to_string())
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+fn non_empty(s: &str) -> Result<&str, AppError> {
+    if s.is_empty() {
+        Err(AppError::Empty)
+    } else {
+        Ok(s)
+    }
+}
+
+fb!(sync, error = AppError, pub parse_sum, (a: &str, b: &str), -> u32, {
+    let a: u32 = non_empty(a)?.parse()?;
+    let b: u32 = b.parse()?;
+    a + b
+});
+
+fb!(async, error = AppError, parse_async, (s: &str), -> u32, {
+    s.parse::<u32>()?
+});
+
+fb!(sync, error = AppError, validate, (s: &str), {
+    non_empty(s)?;
+});
+
+fb!(both, error = AppError, [double, double_async]<T>, (s: &str), -> T, where T: std::str::FromStr<Err = ParseIntError> + std::ops::Add<Output = T> + Copy, {
+    let value: T = s.parse()?;
+    value + value
+});
+
+fb!(async_with_blocking, error = AppError, [load, load_blocking], (s: String), -> u32, {
+    s.parse::<u32>()?
+});
+
+// `cfg(test)` holds for integration tests, so this is the async variant.
+fb!(cfg(test), error = AppError, configured, (s: &str), -> u32, {
+    s.parse::<u32>()?
+});
+
+struct Parser {
+    radix: u32,
+}
+
+impl Parser {
+    fb!(sync, error = AppError, parse, (&self, s: &str), -> u32, {
+        u32::from_str_radix(s, self.radix)?
+    });
+}
+
+#[test]
+fn wraps_sync_bodies_in_ok() {
+    assert_eq!(parse_sum("1", "2"), Ok(3));
+    assert_eq!(parse_sum("", "2"), Err(AppError::Empty));
+    assert!(matches!(parse_sum("1", "x"), Err(AppError::Parse(_))));
+}
+
+#[test]
+fn wraps_async_bodies_in_ok() {
+    assert_eq!(block_on(parse_async("7")), Ok(7));
+    assert!(block_on(parse_async("x")).is_err());
+}
+
+#[test]
+fn supports_unit_functions() {
+    assert_eq!(validate("a"), Ok(()));
+    assert_eq!(validate(""), Err(AppError::Empty));
+}
+
+#[test]
+fn supports_other_modes() {
+    assert_eq!(double::<u32>("4"), Ok(8));
+    assert_eq!(block_on(double_async::<u64>("5")), Ok(10));
+    assert_eq!(load_blocking("3".to_string()), Ok(3));
+    assert!(block_on(load("x".to_string())).is_err());
+    assert_eq!(block_on(configured("9")), Ok(9));
+}
+
+#[test]
+fn supports_methods() {
+    let parser = Parser { radix: 16 };
+    assert_eq!(parser.parse("ff"), Ok(255));
+    assert!(parser.parse("zz").is_err());
+}
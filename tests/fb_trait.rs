@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use flexi_func_declarative::{block_on, fb, fb_trait};
+
+fb_trait! {
+    /// A key-value store.
+    #[fb_trait(async_name = AsyncStore, blanket_impl)]
+    pub trait Store: Sync {
+        fn get(&self, key: &str) -> Option<String>;
+
+        fn put(&mut self, key: String, value: String);
+
+        /// Returns the value, or `default` when it's missing.
+        fn get_or(&self, key: &str, default: &str) -> String {
+            fb_await!(self.get(key)).unwrap_or_else(|| default.to_owned())
+        }
+
+        fn into_len(self) -> usize;
+    }
+}
+
+fb_trait! {
+    #[fb_trait(async_name = AsyncCounter)]
+    trait Counter: Send + Sync {
+        fn count(&self) -> usize;
+
+        fn reset(&self,) {}
+    }
+}
+
+struct Memory {
+    values: HashMap<String, String>,
+}
+
+impl Store for Memory {
+    fn get(&self, key: &str) -> Option<String> {
+        self.values.get(key).cloned()
+    }
+
+    fn put(&mut self, key: String, value: String) {
+        self.values.insert(key, value);
+    }
+
+    fn into_len(self) -> usize {
+        self.values.len()
+    }
+}
+
+struct Shared {
+    count: Mutex<usize>,
+}
+
+impl AsyncCounter for Shared {
+    fb!(trait async, fn count(&self) -> usize {
+        *self.count.lock().unwrap()
+    });
+}
+
+#[test]
+fn generates_the_sync_trait() {
+    let mut memory = Memory {
+        values: HashMap::new(),
+    };
+    Store::put(&mut memory, "a".to_owned(), "1".to_owned());
+    assert_eq!(Store::get(&memory, "a"), Some("1".to_owned()));
+    assert_eq!(Store::get_or(&memory, "b", "2"), "2");
+    assert_eq!(Store::into_len(memory), 1);
+}
+
+#[test]
+fn blanket_impl_provides_the_async_trait() {
+    let mut memory = Memory {
+        values: HashMap::new(),
+    };
+    block_on(AsyncStore::put(&mut memory, "a".to_owned(), "1".to_owned()));
+    assert_eq!(block_on(AsyncStore::get(&memory, "a")), Some("1".to_owned()));
+    assert_eq!(block_on(AsyncStore::get_or(&memory, "b", "2")), "2");
+    assert_eq!(block_on(AsyncStore::into_len(memory)), 1);
+}
+
+#[test]
+fn async_trait_can_be_implemented_directly() {
+    let shared = Shared {
+        count: Mutex::new(3),
+    };
+    assert_eq!(block_on(shared.count()), 3);
+    block_on(shared.reset());
+}
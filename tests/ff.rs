@@ -0,0 +1,529 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use flexi_func_declarative::{block_on, ff};
+
+ff! {
+    #[ff(async_name = compute_async)]
+    pub fn compute(data: Vec<u8>) -> usize {
+        data.len()
+    }
+
+    /// Doubles the sum of `values`.
+    #[inline]
+    #[ff(async_name = double_sum_async)]
+    fn double_sum(values: &[u32]) -> u32 {
+        let mut total = 0;
+        for value in values {
+            total += value;
+        }
+        total * 2
+    }
+
+    #[ff(async_name = record_async)]
+    fn record(log: &mut Vec<String>, line: &str) {
+        log.push(line.to_owned());
+    }
+}
+
+ff! {
+    #[must_use]
+    #[ff(async_name = describe_async)]
+    pub(crate) fn describe<T>(value: T, times: usize) -> String
+    where
+        T: Display,
+    {
+        value.to_string().repeat(times)
+    }
+
+    #[ff(async_name = total_len_async)]
+    fn total_len<'a, I: IntoIterator<Item = &'a str>>(items: I) -> usize {
+        let mut total = 0;
+        for item in items {
+            total += fb_await!(compute(item.as_bytes().to_vec()), compute_async(item.as_bytes().to_vec()));
+        }
+        total
+    }
+}
+
+// Forty twins with generics, `where` clauses and patterns, each with its own `#[ff(...)]`
+// attribute. Each one adds a single level of recursion.
+mod codec {
+    use std::collections::BTreeMap;
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    use flexi_func_declarative::ff;
+
+    ff! {
+        #[ff(async_name = parse_0_async)]
+        pub fn parse_0<T>(text: &str, default: T) -> Result<T, String>
+        where
+            T: FromStr + Copy,
+            T::Err: Display,
+        {
+            if text.is_empty() {
+                return Ok(default);
+            }
+            text.trim().parse().map_err(|err: T::Err| format!("0: {}", err))
+        }
+
+        /// Wraps every part in `prefix` and `suffix`.
+        #[ff(async_name = wrap_1_async)]
+        pub(crate) fn wrap_1<'a, I: IntoIterator<Item = &'a str>>(parts: I, (prefix, suffix): (&str, &str)) -> String {
+            let mut out = String::new();
+            for part in parts {
+                out.push_str(&format!("{}{}{}", prefix, part, suffix));
+            }
+            out
+        }
+
+        #[ff(async_name = checksum_2_async)]
+        pub(super) fn checksum_2<B: AsRef<[u8]> + ?Sized>(data: &B, mut seed: u32) -> u32 {
+            for byte in data.as_ref() {
+                seed = seed.wrapping_mul(31).wrapping_add(u32::from(*byte));
+            }
+            seed + 2
+        }
+
+        #[inline]
+        #[ff(async_name = merge_3_async)]
+        pub fn merge_3<K: Ord + Clone, V>(left: BTreeMap<K, V>, right: BTreeMap<K, V>) -> BTreeMap<K, V>
+        where
+            V: Clone,
+        {
+            let mut merged = left;
+            merged.extend(right);
+            merged
+        }
+
+        #[ff(async_name = parse_4_async)]
+        pub fn parse_4<T>(text: &str, default: T) -> Result<T, String>
+        where
+            T: FromStr + Copy,
+            T::Err: Display,
+        {
+            if text.is_empty() {
+                return Ok(default);
+            }
+            text.trim().parse().map_err(|err: T::Err| format!("4: {}", err))
+        }
+
+        /// Wraps every part in `prefix` and `suffix`.
+        #[ff(async_name = wrap_5_async)]
+        pub(crate) fn wrap_5<'a, I: IntoIterator<Item = &'a str>>(parts: I, (prefix, suffix): (&str, &str)) -> String {
+            let mut out = String::new();
+            for part in parts {
+                out.push_str(&format!("{}{}{}", prefix, part, suffix));
+            }
+            out
+        }
+
+        #[ff(async_name = checksum_6_async)]
+        pub(super) fn checksum_6<B: AsRef<[u8]> + ?Sized>(data: &B, mut seed: u32) -> u32 {
+            for byte in data.as_ref() {
+                seed = seed.wrapping_mul(31).wrapping_add(u32::from(*byte));
+            }
+            seed + 6
+        }
+
+        #[inline]
+        #[ff(async_name = merge_7_async)]
+        pub fn merge_7<K: Ord + Clone, V>(left: BTreeMap<K, V>, right: BTreeMap<K, V>) -> BTreeMap<K, V>
+        where
+            V: Clone,
+        {
+            let mut merged = left;
+            merged.extend(right);
+            merged
+        }
+
+        #[ff(async_name = parse_8_async)]
+        pub fn parse_8<T>(text: &str, default: T) -> Result<T, String>
+        where
+            T: FromStr + Copy,
+            T::Err: Display,
+        {
+            if text.is_empty() {
+                return Ok(default);
+            }
+            text.trim().parse().map_err(|err: T::Err| format!("8: {}", err))
+        }
+
+        /// Wraps every part in `prefix` and `suffix`.
+        #[ff(async_name = wrap_9_async)]
+        pub(crate) fn wrap_9<'a, I: IntoIterator<Item = &'a str>>(parts: I, (prefix, suffix): (&str, &str)) -> String {
+            let mut out = String::new();
+            for part in parts {
+                out.push_str(&format!("{}{}{}", prefix, part, suffix));
+            }
+            out
+        }
+
+        #[ff(async_name = checksum_10_async)]
+        pub(super) fn checksum_10<B: AsRef<[u8]> + ?Sized>(data: &B, mut seed: u32) -> u32 {
+            for byte in data.as_ref() {
+                seed = seed.wrapping_mul(31).wrapping_add(u32::from(*byte));
+            }
+            seed + 10
+        }
+
+        #[inline]
+        #[ff(async_name = merge_11_async)]
+        pub fn merge_11<K: Ord + Clone, V>(left: BTreeMap<K, V>, right: BTreeMap<K, V>) -> BTreeMap<K, V>
+        where
+            V: Clone,
+        {
+            let mut merged = left;
+            merged.extend(right);
+            merged
+        }
+
+        #[ff(async_name = parse_12_async)]
+        pub fn parse_12<T>(text: &str, default: T) -> Result<T, String>
+        where
+            T: FromStr + Copy,
+            T::Err: Display,
+        {
+            if text.is_empty() {
+                return Ok(default);
+            }
+            text.trim().parse().map_err(|err: T::Err| format!("12: {}", err))
+        }
+
+        /// Wraps every part in `prefix` and `suffix`.
+        #[ff(async_name = wrap_13_async)]
+        pub(crate) fn wrap_13<'a, I: IntoIterator<Item = &'a str>>(parts: I, (prefix, suffix): (&str, &str)) -> String {
+            let mut out = String::new();
+            for part in parts {
+                out.push_str(&format!("{}{}{}", prefix, part, suffix));
+            }
+            out
+        }
+
+        #[ff(async_name = checksum_14_async)]
+        pub(super) fn checksum_14<B: AsRef<[u8]> + ?Sized>(data: &B, mut seed: u32) -> u32 {
+            for byte in data.as_ref() {
+                seed = seed.wrapping_mul(31).wrapping_add(u32::from(*byte));
+            }
+            seed + 14
+        }
+
+        #[inline]
+        #[ff(async_name = merge_15_async)]
+        pub fn merge_15<K: Ord + Clone, V>(left: BTreeMap<K, V>, right: BTreeMap<K, V>) -> BTreeMap<K, V>
+        where
+            V: Clone,
+        {
+            let mut merged = left;
+            merged.extend(right);
+            merged
+        }
+
+        #[ff(async_name = parse_16_async)]
+        pub fn parse_16<T>(text: &str, default: T) -> Result<T, String>
+        where
+            T: FromStr + Copy,
+            T::Err: Display,
+        {
+            if text.is_empty() {
+                return Ok(default);
+            }
+            text.trim().parse().map_err(|err: T::Err| format!("16: {}", err))
+        }
+
+        /// Wraps every part in `prefix` and `suffix`.
+        #[ff(async_name = wrap_17_async)]
+        pub(crate) fn wrap_17<'a, I: IntoIterator<Item = &'a str>>(parts: I, (prefix, suffix): (&str, &str)) -> String {
+            let mut out = String::new();
+            for part in parts {
+                out.push_str(&format!("{}{}{}", prefix, part, suffix));
+            }
+            out
+        }
+
+        #[ff(async_name = checksum_18_async)]
+        pub(super) fn checksum_18<B: AsRef<[u8]> + ?Sized>(data: &B, mut seed: u32) -> u32 {
+            for byte in data.as_ref() {
+                seed = seed.wrapping_mul(31).wrapping_add(u32::from(*byte));
+            }
+            seed + 18
+        }
+
+        #[inline]
+        #[ff(async_name = merge_19_async)]
+        pub fn merge_19<K: Ord + Clone, V>(left: BTreeMap<K, V>, right: BTreeMap<K, V>) -> BTreeMap<K, V>
+        where
+            V: Clone,
+        {
+            let mut merged = left;
+            merged.extend(right);
+            merged
+        }
+
+        #[ff(async_name = parse_20_async)]
+        pub fn parse_20<T>(text: &str, default: T) -> Result<T, String>
+        where
+            T: FromStr + Copy,
+            T::Err: Display,
+        {
+            if text.is_empty() {
+                return Ok(default);
+            }
+            text.trim().parse().map_err(|err: T::Err| format!("20: {}", err))
+        }
+
+        /// Wraps every part in `prefix` and `suffix`.
+        #[ff(async_name = wrap_21_async)]
+        pub(crate) fn wrap_21<'a, I: IntoIterator<Item = &'a str>>(parts: I, (prefix, suffix): (&str, &str)) -> String {
+            let mut out = String::new();
+            for part in parts {
+                out.push_str(&format!("{}{}{}", prefix, part, suffix));
+            }
+            out
+        }
+
+        #[ff(async_name = checksum_22_async)]
+        pub(super) fn checksum_22<B: AsRef<[u8]> + ?Sized>(data: &B, mut seed: u32) -> u32 {
+            for byte in data.as_ref() {
+                seed = seed.wrapping_mul(31).wrapping_add(u32::from(*byte));
+            }
+            seed + 22
+        }
+
+        #[inline]
+        #[ff(async_name = merge_23_async)]
+        pub fn merge_23<K: Ord + Clone, V>(left: BTreeMap<K, V>, right: BTreeMap<K, V>) -> BTreeMap<K, V>
+        where
+            V: Clone,
+        {
+            let mut merged = left;
+            merged.extend(right);
+            merged
+        }
+
+        #[ff(async_name = parse_24_async)]
+        pub fn parse_24<T>(text: &str, default: T) -> Result<T, String>
+        where
+            T: FromStr + Copy,
+            T::Err: Display,
+        {
+            if text.is_empty() {
+                return Ok(default);
+            }
+            text.trim().parse().map_err(|err: T::Err| format!("24: {}", err))
+        }
+
+        /// Wraps every part in `prefix` and `suffix`.
+        #[ff(async_name = wrap_25_async)]
+        pub(crate) fn wrap_25<'a, I: IntoIterator<Item = &'a str>>(parts: I, (prefix, suffix): (&str, &str)) -> String {
+            let mut out = String::new();
+            for part in parts {
+                out.push_str(&format!("{}{}{}", prefix, part, suffix));
+            }
+            out
+        }
+
+        #[ff(async_name = checksum_26_async)]
+        pub(super) fn checksum_26<B: AsRef<[u8]> + ?Sized>(data: &B, mut seed: u32) -> u32 {
+            for byte in data.as_ref() {
+                seed = seed.wrapping_mul(31).wrapping_add(u32::from(*byte));
+            }
+            seed + 26
+        }
+
+        #[inline]
+        #[ff(async_name = merge_27_async)]
+        pub fn merge_27<K: Ord + Clone, V>(left: BTreeMap<K, V>, right: BTreeMap<K, V>) -> BTreeMap<K, V>
+        where
+            V: Clone,
+        {
+            let mut merged = left;
+            merged.extend(right);
+            merged
+        }
+
+        #[ff(async_name = parse_28_async)]
+        pub fn parse_28<T>(text: &str, default: T) -> Result<T, String>
+        where
+            T: FromStr + Copy,
+            T::Err: Display,
+        {
+            if text.is_empty() {
+                return Ok(default);
+            }
+            text.trim().parse().map_err(|err: T::Err| format!("28: {}", err))
+        }
+
+        /// Wraps every part in `prefix` and `suffix`.
+        #[ff(async_name = wrap_29_async)]
+        pub(crate) fn wrap_29<'a, I: IntoIterator<Item = &'a str>>(parts: I, (prefix, suffix): (&str, &str)) -> String {
+            let mut out = String::new();
+            for part in parts {
+                out.push_str(&format!("{}{}{}", prefix, part, suffix));
+            }
+            out
+        }
+
+        #[ff(async_name = checksum_30_async)]
+        pub(super) fn checksum_30<B: AsRef<[u8]> + ?Sized>(data: &B, mut seed: u32) -> u32 {
+            for byte in data.as_ref() {
+                seed = seed.wrapping_mul(31).wrapping_add(u32::from(*byte));
+            }
+            seed + 30
+        }
+
+        #[inline]
+        #[ff(async_name = merge_31_async)]
+        pub fn merge_31<K: Ord + Clone, V>(left: BTreeMap<K, V>, right: BTreeMap<K, V>) -> BTreeMap<K, V>
+        where
+            V: Clone,
+        {
+            let mut merged = left;
+            merged.extend(right);
+            merged
+        }
+
+        #[ff(async_name = parse_32_async)]
+        pub fn parse_32<T>(text: &str, default: T) -> Result<T, String>
+        where
+            T: FromStr + Copy,
+            T::Err: Display,
+        {
+            if text.is_empty() {
+                return Ok(default);
+            }
+            text.trim().parse().map_err(|err: T::Err| format!("32: {}", err))
+        }
+
+        /// Wraps every part in `prefix` and `suffix`.
+        #[ff(async_name = wrap_33_async)]
+        pub(crate) fn wrap_33<'a, I: IntoIterator<Item = &'a str>>(parts: I, (prefix, suffix): (&str, &str)) -> String {
+            let mut out = String::new();
+            for part in parts {
+                out.push_str(&format!("{}{}{}", prefix, part, suffix));
+            }
+            out
+        }
+
+        #[ff(async_name = checksum_34_async)]
+        pub(super) fn checksum_34<B: AsRef<[u8]> + ?Sized>(data: &B, mut seed: u32) -> u32 {
+            for byte in data.as_ref() {
+                seed = seed.wrapping_mul(31).wrapping_add(u32::from(*byte));
+            }
+            seed + 34
+        }
+
+        #[inline]
+        #[ff(async_name = merge_35_async)]
+        pub fn merge_35<K: Ord + Clone, V>(left: BTreeMap<K, V>, right: BTreeMap<K, V>) -> BTreeMap<K, V>
+        where
+            V: Clone,
+        {
+            let mut merged = left;
+            merged.extend(right);
+            merged
+        }
+
+        #[ff(async_name = parse_36_async)]
+        pub fn parse_36<T>(text: &str, default: T) -> Result<T, String>
+        where
+            T: FromStr + Copy,
+            T::Err: Display,
+        {
+            if text.is_empty() {
+                return Ok(default);
+            }
+            text.trim().parse().map_err(|err: T::Err| format!("36: {}", err))
+        }
+
+        /// Wraps every part in `prefix` and `suffix`.
+        #[ff(async_name = wrap_37_async)]
+        pub(crate) fn wrap_37<'a, I: IntoIterator<Item = &'a str>>(parts: I, (prefix, suffix): (&str, &str)) -> String {
+            let mut out = String::new();
+            for part in parts {
+                out.push_str(&format!("{}{}{}", prefix, part, suffix));
+            }
+            out
+        }
+
+        #[ff(async_name = checksum_38_async)]
+        pub(super) fn checksum_38<B: AsRef<[u8]> + ?Sized>(data: &B, mut seed: u32) -> u32 {
+            for byte in data.as_ref() {
+                seed = seed.wrapping_mul(31).wrapping_add(u32::from(*byte));
+            }
+            seed + 38
+        }
+
+        #[inline]
+        #[ff(async_name = merge_39_async)]
+        pub fn merge_39<K: Ord + Clone, V>(left: BTreeMap<K, V>, right: BTreeMap<K, V>) -> BTreeMap<K, V>
+        where
+            V: Clone,
+        {
+            let mut merged = left;
+            merged.extend(right);
+            merged
+        }
+    }
+}
+
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    ff! {
+        #[ff(async_name = bump_async)]
+        pub fn bump(&mut self, by: u32) -> u32 {
+            self.count += by;
+            self.count
+        }
+    }
+}
+
+#[test]
+fn generates_sync_and_async_twins() {
+    assert_eq!(compute(vec![1, 2, 3]), 3);
+    assert_eq!(block_on(compute_async(vec![1, 2, 3])), 3);
+}
+
+#[test]
+fn forwards_attributes_and_docs() {
+    assert_eq!(double_sum(&[1, 2, 3]), 12);
+    assert_eq!(block_on(double_sum_async(&[1, 2, 3])), 12);
+}
+
+#[test]
+fn supports_unit_functions() {
+    let mut log = Vec::new();
+    record(&mut log, "sync");
+    block_on(record_async(&mut log, "async"));
+    assert_eq!(log, ["sync", "async"]);
+}
+
+#[test]
+fn supports_generics_and_where_clauses() {
+    assert_eq!(describe(4, 3), "444");
+    assert_eq!(block_on(describe_async("ab", 2)), "abab");
+    assert_eq!(total_len(["ab", "cde"]), 5);
+    assert_eq!(block_on(total_len_async(["ab", "cde"])), 5);
+}
+
+#[test]
+fn supports_large_numbers_of_generic_functions() {
+    assert_eq!(codec::parse_36::<u8>(" 7 ", 0), Ok(7));
+    assert_eq!(block_on(codec::parse_36_async::<u8>("", 3)), Ok(3));
+    assert!(codec::parse_36::<u8>("x", 0).unwrap_err().starts_with("36: "));
+    assert_eq!(block_on(codec::wrap_37_async(["a", "b"], ("<", ">"))), "<a><b>");
+    assert_eq!(codec::checksum_38("ab", 1), block_on(codec::checksum_38_async(&[97u8, 98][..], 1)));
+    let merged = block_on(codec::merge_39_async(BTreeMap::from([(1, "a")]), BTreeMap::from([(2, "b")])));
+    assert_eq!(merged.len(), 2);
+}
+
+#[test]
+fn supports_methods() {
+    let mut counter = Counter { count: 0 };
+    assert_eq!(counter.bump(2), 2);
+    assert_eq!(block_on(counter.bump_async(3)), 5);
+}
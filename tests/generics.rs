@@ -0,0 +1,68 @@
+mod common;
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use flexi_func_declarative::fb;
+
+fb!(sync, parse<T: FromStr>, (s: &str), -> Result<T, T::Err>, {
+    s.parse()
+});
+
+fb!(async, parse_async<T: FromStr>, (s: String), -> Result<T, T::Err>, {
+    s.parse()
+});
+
+fb!(sync, longest<'a>, (a: &'a str, b: &'a str), -> &'a str, {
+    if a.len() >= b.len() { a } else { b }
+});
+
+fb!(sync, first<T: Copy, const N: usize>, (items: [T; N]), -> Option<T>, {
+    items.first().copied()
+});
+
+fb!(sync, total<T: Into<Vec<Vec<u8>>>>, (value: T), -> usize, {
+    value.into().iter().map(Vec::len).sum()
+});
+
+fb!(sync, join_all<I, T>, (items: I, sep: &str), -> String, where I: IntoIterator<Item = T>, T: Display, {
+    items.into_iter().map(|item| item.to_string()).collect::<Vec<_>>().join(sep)
+});
+
+fb!(async, describe<T>, (value: T), -> String, where T: Display + Send, {
+    format!("<{}>", value)
+});
+
+fb!(sync, apply<F: Fn(u32) -> u32>, (f: F, x: u32), -> u32, {
+    f(x)
+});
+
+#[test]
+fn type_parameters_with_bounds() {
+    assert_eq!(parse::<u32>("42"), Ok(42));
+    assert!(parse::<u32>("nope").is_err());
+    assert_eq!(common::block_on(parse_async::<i64>("-7".to_string())), Ok(-7));
+}
+
+#[test]
+fn lifetime_parameters() {
+    assert_eq!(longest("short", "longer"), "longer");
+}
+
+#[test]
+fn const_parameters() {
+    assert_eq!(first([3, 2, 1]), Some(3));
+    assert_eq!(first::<u8, 0>([]), None);
+}
+
+#[test]
+fn nested_angle_brackets_in_bounds() {
+    assert_eq!(total(vec![vec![1u8, 2], vec![3]]), 3);
+    assert_eq!(apply(|x| x + 1, 1), 2);
+}
+
+#[test]
+fn where_clauses() {
+    assert_eq!(join_all([1, 2, 3], ", "), "1, 2, 3");
+    assert_eq!(common::block_on(describe(5)), "<5>");
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use flexi_func_declarative::fb;
+
+macro_rules! define_sum {
+    ($mode:tt) => {
+        fb!($mode, pub sum, (values: Vec<u32>), -> u32, {
+            let mut total = 0;
+            for value in values {
+                total += fb_await!(source::read(value));
+            }
+            total
+        });
+    };
+}
+
+mod blocking {
+    use flexi_func_declarative::fb;
+
+    mod source {
+        pub fn read(value: u32) -> u32 {
+            value
+        }
+    }
+
+    define_sum!(sync);
+}
+
+mod nonblocking {
+    use flexi_func_declarative::fb;
+
+    mod source {
+        pub async fn read(value: u32) -> u32 {
+            crate::common::yield_now().await;
+            value
+        }
+    }
+
+    define_sum!(async);
+}
+
+fn double(value: u32) -> u32 {
+    value * 2
+}
+
+async fn double_async(value: u32) -> u32 {
+    value * 2
+}
+
+#[test]
+fn one_body_serves_both_function_arms() {
+    assert_eq!(blocking::sum(vec![1, 2, 3]), 6);
+    assert_eq!(common::block_on(nonblocking::sum(vec![1, 2, 3])), 6);
+}
+
+#[test]
+fn marker_works_in_closures() {
+    let sync_closure = fb!(sync, closure, { fb_await!(double(2), double_async(2)) });
+    let async_closure = fb!(async, closure, { fb_await!(double(3), double_async(3)) });
+    assert_eq!(sync_closure(), 4);
+    assert_eq!(common::block_on(async_closure()), 6);
+}
+
+#[test]
+fn marker_works_in_execute_blocks() {
+    let sync_value = fb!(sync, execute, { fb_await!(double(4)) });
+    let async_value = fb!(async, execute, { fb_await!(double_async(5)) });
+    assert_eq!(sync_value, 8);
+    assert_eq!(common::block_on(async_value), 10);
+}
+
+#[test]
+fn nested_bodies_use_their_own_mode() {
+    let future = fb!(async, execute, {
+        let inner = fb!(sync, execute, { fb_await!(double(1), double_async(1)) });
+        fb_await!(double_async(inner))
+    });
+    assert_eq!(common::block_on(future), 4);
+}
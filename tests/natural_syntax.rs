@@ -0,0 +1,92 @@
+use std::fmt::Display;
+
+use flexi_func_declarative::{block_on, fb};
+
+fb!(sync, fn greet(name: String) -> String {
+    format!("Hello, {}", name)
+});
+
+fb!(
+    /// Adds two numbers.
+    #[inline]
+    async,
+    pub fn add(a: u32, b: u32) -> u32 {
+        a + b
+    }
+);
+
+fb!(sync, pub(crate) fn log(lines: &mut Vec<String>, line: &str) {
+    lines.push(line.to_owned());
+});
+
+fb!(sync, fn describe<T>(value: T, times: usize) -> String
+where
+    T: Display,
+{
+    value.to_string().repeat(times)
+});
+
+fb!(sync, error = std::num::ParseIntError, fn parse(s: &str) -> u32 {
+    s.parse()?
+});
+
+fb!(both, pub fn [total, total_async](values: &[u32]) -> u32 {
+    values.iter().sum()
+});
+
+fb!(async_with_blocking, fn [length, length_blocking](text: String) -> usize {
+    text.len()
+});
+
+// `cfg(test)` holds for integration tests, so this is the async variant.
+fb!(cfg(test), fn configured(value: u32) -> u32 {
+    fb_await!(std::future::ready(value))
+});
+
+// The comma-separated form keeps working next to the natural one.
+fb!(sync, shout, (name: &str), -> String, {
+    name.to_uppercase()
+});
+
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fb!(sync, fn bump(&mut self, by: u32) -> u32 {
+        self.count += by;
+        self.count
+    });
+}
+
+#[test]
+fn accepts_regular_function_definitions() {
+    assert_eq!(greet("ferris".to_string()), "Hello, ferris");
+    assert_eq!(block_on(add(1, 2)), 3);
+    let mut lines = Vec::new();
+    log(&mut lines, "one");
+    assert_eq!(lines, ["one"]);
+    assert_eq!(shout("hi"), "HI");
+}
+
+#[test]
+fn accepts_generics_and_where_clauses() {
+    assert_eq!(describe('a', 3), "aaa");
+}
+
+#[test]
+fn works_with_every_function_mode() {
+    assert_eq!(parse("7"), Ok(7));
+    assert!(parse("x").is_err());
+    assert_eq!(total(&[1, 2, 3]), 6);
+    assert_eq!(block_on(total_async(&[1, 2, 3])), 6);
+    assert_eq!(length_blocking("abc".to_string()), 3);
+    assert_eq!(block_on(length("abcd".to_string())), 4);
+    assert_eq!(block_on(configured(9)), 9);
+}
+
+#[test]
+fn accepts_methods() {
+    let mut counter = Counter { count: 0 };
+    assert_eq!(counter.bump(4), 4);
+}
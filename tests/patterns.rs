@@ -0,0 +1,87 @@
+mod common;
+
+use flexi_func_declarative::fb;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fb!(sync, fill, (mut buf: Vec<u8>, len: usize,), -> Vec<u8>, {
+    buf.resize(len, 0xff);
+    buf
+});
+
+fb!(async, fill_async, (mut buf: Vec<u8>, len: usize,), -> Vec<u8>, {
+    common::yield_now().await;
+    buf.resize(len, 0xff);
+    buf
+});
+
+fb!(sync, sum_pair, ((a, b): (u32, u32)), -> u32, {
+    a + b
+});
+
+fb!(async, sum_pair_async, ((a, b): (u32, u32), [c, d]: [u32; 2]), -> u32, {
+    common::yield_now().await;
+    a + b + c + d
+});
+
+fb!(sync, manhattan, (Point { x, y }: Point, origin: Point), -> i32, {
+    (x - origin.x).abs() + (y - origin.y).abs()
+});
+
+fb!(async, manhattan_async, (Point { x, y: vertical }: Point, _: ()), -> i32, {
+    common::yield_now().await;
+    x.abs() + vertical.abs()
+});
+
+fb!(async, greet_owned, (name: String, &count: &usize), -> String, {
+    common::yield_now().await;
+    name.repeat(count)
+});
+
+#[derive(Debug)]
+struct Accumulator {
+    values: Vec<u32>,
+}
+
+impl Accumulator {
+    fb!(async, push_pair, (&mut self, (a, b): (u32, u32), mut extra: Vec<u32>), -> usize, {
+        common::yield_now().await;
+        self.values.extend([a, b]);
+        self.values.append(&mut extra);
+        self.values.len()
+    });
+}
+
+#[test]
+fn mutable_bindings() {
+    assert_eq!(fill(vec![1], 3), vec![1, 0xff, 0xff]);
+    assert_eq!(common::block_on(fill_async(vec![], 2)), vec![0xff, 0xff]);
+}
+
+#[test]
+fn tuple_and_slice_patterns() {
+    assert_eq!(sum_pair((1, 2)), 3);
+    assert_eq!(common::block_on(sum_pair_async((1, 2), [3, 4])), 10);
+}
+
+#[test]
+fn struct_patterns() {
+    let origin = Point { x: 1, y: 1 };
+    assert_eq!(manhattan(Point { x: 4, y: -3 }, origin), 7);
+    assert_eq!(common::block_on(manhattan_async(Point { x: 4, y: -3 }, ())), 7);
+}
+
+#[test]
+fn async_bindings_outlive_await_points() {
+    let count = 2;
+    let future = greet_owned("hi".to_string(), &count);
+    assert_eq!(common::block_on(future), "hihi");
+
+    let mut acc = Accumulator { values: vec![] };
+    assert_eq!(common::block_on(acc.push_pair((1, 2), vec![3])), 3);
+    assert_eq!(acc.values, [1, 2, 3]);
+}
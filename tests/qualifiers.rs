@@ -0,0 +1,95 @@
+use std::ffi::c_int;
+
+use flexi_func_declarative::{block_on, fb};
+
+fb!(sync, pub const fn answer() -> u32 {
+    42
+});
+
+fb!(sync, const double, (value: u32), -> u32, {
+    value * 2
+});
+
+const ANSWER: u32 = answer();
+const DOUBLED: u32 = double(21);
+
+fb!(sync, unsafe fn read(ptr: *const u32) -> u32 {
+    *ptr
+});
+
+fb!(async, unsafe fn read_async(ptr: *const u32) -> u32 {
+    *ptr
+});
+
+fb!(sync, pub unsafe extern "C" fn shim_add(a: c_int, b: c_int) -> c_int {
+    a + b
+});
+
+fb!(sync, extern "C" fn shim_negate(value: c_int) -> c_int {
+    -value
+});
+
+fb!(sync, const unsafe first, (values: *const [u8; 2]), -> u8, {
+    (*values)[0]
+});
+
+// `cfg(test)` holds for integration tests, so this is the async variant.
+fb!(cfg(test), unsafe fn configured(ptr: *const u32) -> u32 {
+    *ptr
+});
+
+fb! {
+    sync {
+        const fn square(value: u32) -> u32 {
+            value * value
+        }
+
+        unsafe extern "C" fn shim_square(value: c_int) -> c_int {
+            value * value
+        }
+    }
+}
+
+struct Buffer {
+    data: [u8; 4],
+}
+
+impl Buffer {
+    fb!(sync, const fn len(&self) -> usize {
+        self.data.len()
+    });
+
+    fb!(async, unsafe fn get_unchecked(&self, index: usize) -> u8 {
+        *self.data.get_unchecked(index)
+    });
+}
+
+#[test]
+fn generates_const_functions() {
+    assert_eq!(ANSWER, 42);
+    assert_eq!(DOUBLED, 42);
+    const SQUARED: u32 = square(5);
+    assert_eq!(SQUARED, 25);
+    const LEN: usize = Buffer { data: [0; 4] }.len();
+    assert_eq!(LEN, 4);
+}
+
+#[test]
+fn generates_unsafe_functions() {
+    let value = 7;
+    assert_eq!(unsafe { read(&value) }, 7);
+    assert_eq!(block_on(unsafe { read_async(&value) }), 7);
+    assert_eq!(block_on(unsafe { configured(&value) }), 7);
+    assert_eq!(unsafe { first(&[3, 4]) }, 3);
+    let buffer = Buffer { data: [1, 2, 3, 4] };
+    assert_eq!(block_on(unsafe { buffer.get_unchecked(2) }), 3);
+}
+
+#[test]
+fn generates_extern_functions() {
+    let add: unsafe extern "C" fn(c_int, c_int) -> c_int = shim_add;
+    let negate: extern "C" fn(c_int) -> c_int = shim_negate;
+    assert_eq!(unsafe { add(2, 3) }, 5);
+    assert_eq!(negate(4), -4);
+    assert_eq!(unsafe { shim_square(3) }, 9);
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use flexi_func_declarative::fb;
+
+#[derive(Debug, Default)]
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fb!(sync, pub get, (&self), -> u32, {
+        self.count
+    });
+
+    fb!(async, pub get_async, (&self), -> u32, {
+        self.count
+    });
+
+    fb!(sync, pub add, (&mut self, amount: u32), -> u32, {
+        self.count += amount;
+        self.count
+    });
+
+    fb!(async, pub add_async, (&mut self, amount: u32), -> u32, {
+        self.count += amount;
+        self.count
+    });
+
+    fb!(sync, into_count, (self), -> u32, {
+        self.count
+    });
+
+    fb!(async, into_count_async, (self), -> u32, {
+        self.count
+    });
+
+    fb!(sync, reset, (mut self), -> Self, {
+        self.count = 0;
+        self
+    });
+
+    fb!(async, reset_async, (mut self), -> Self, {
+        self.count = 0;
+        self
+    });
+
+    fb!(sync, count_ref<'a>, (&'a self), -> &'a u32, {
+        &self.count
+    });
+
+    fb!(async, count_mut<'a>, (&'a mut self, ), -> &'a mut u32, {
+        &mut self.count
+    });
+
+    fb!(sync, boxed_count, (self: Rc<Self>), -> u32, {
+        self.count
+    });
+}
+
+struct Countdown {
+    remaining: u32,
+}
+
+impl Countdown {
+    fb!(sync, tick, (self: Pin<&mut Self>), -> Poll<()>, {
+        let this = self.get_mut();
+        if this.remaining == 0 {
+            Poll::Ready(())
+        } else {
+            this.remaining -= 1;
+            Poll::Pending
+        }
+    });
+
+    fb!(async, remaining, (self: Pin<&mut Self>, extra: u32), -> u32, {
+        self.remaining + extra
+    });
+}
+
+impl Future for Countdown {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let poll = self.tick();
+        if poll.is_pending() {
+            cx.waker().wake_by_ref();
+        }
+        poll
+    }
+}
+
+#[test]
+fn shared_and_mutable_references() {
+    let mut counter = Counter::default();
+    assert_eq!(counter.add(2), 2);
+    assert_eq!(common::block_on(counter.add_async(3)), 5);
+    assert_eq!(counter.get(), 5);
+    assert_eq!(common::block_on(counter.get_async()), 5);
+}
+
+#[test]
+fn by_value_receivers() {
+    assert_eq!(Counter { count: 4 }.into_count(), 4);
+    assert_eq!(common::block_on(Counter { count: 4 }.into_count_async()), 4);
+    assert_eq!(Counter { count: 4 }.reset().count, 0);
+    assert_eq!(common::block_on(Counter { count: 4 }.reset_async()).count, 0);
+}
+
+#[test]
+fn references_with_explicit_lifetimes() {
+    let mut counter = Counter { count: 1 };
+    assert_eq!(*counter.count_ref(), 1);
+    *common::block_on(counter.count_mut()) = 9;
+    assert_eq!(counter.get(), 9);
+}
+
+#[test]
+fn typed_receivers() {
+    assert_eq!(Rc::new(Counter { count: 6 }).boxed_count(), 6);
+    common::block_on(Countdown { remaining: 3 });
+    let mut countdown = Countdown { remaining: 2 };
+    assert_eq!(common::block_on(Pin::new(&mut countdown).remaining(1)), 3);
+}
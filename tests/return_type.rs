@@ -0,0 +1,72 @@
+mod common;
+
+use std::cell::Cell;
+use std::fmt::Debug;
+
+use flexi_func_declarative::fb;
+
+thread_local! {
+    static CALLS: Cell<u32> = const { Cell::new(0) };
+}
+
+fb!(sync, record, (amount: u32), {
+    CALLS.with(|calls| calls.set(calls.get() + amount));
+});
+
+fb!(async, record_async, (amount: u32), {
+    common::yield_now().await;
+    CALLS.with(|calls| calls.set(calls.get() + amount));
+});
+
+fb!(sync, describe<T>, (value: T, out: &mut String), where T: Debug, {
+    out.push_str(&format!("{:?}", value));
+});
+
+fb!(async, describe_async<T>, (value: T, out: &mut String), where T: Debug, {
+    out.push_str(&format!("{:?}", value));
+});
+
+struct Log(Vec<&'static str>);
+
+impl Log {
+    fb!(sync, push, (&mut self, line: &'static str), {
+        self.0.push(line);
+    });
+}
+
+// A function literally named `closure` still resolves to the function arm.
+fb!(sync, closure, (), {
+    record(100);
+});
+
+#[test]
+fn unit_returning_functions() {
+    record(1);
+    common::block_on(record_async(2));
+    closure();
+    assert_eq!(CALLS.with(Cell::get), 103);
+}
+
+#[test]
+fn unit_returning_functions_with_where_clause() {
+    let mut out = String::new();
+    describe(1, &mut out);
+    common::block_on(describe_async("two", &mut out));
+    assert_eq!(out, "1\"two\"");
+}
+
+#[test]
+fn unit_returning_methods() {
+    let mut log = Log(vec![]);
+    log.push("a");
+    log.push("b");
+    assert_eq!(log.0, ["a", "b"]);
+}
+
+#[test]
+fn closures_and_blocks_are_unaffected() {
+    let sync_closure = fb!(sync, closure, { 1 });
+    let async_closure = fb!(async, closure, { 2 });
+    let value = fb!(sync, execute, { 3 });
+    assert_eq!(sync_closure() + common::block_on(async_closure()) + value, 6);
+}
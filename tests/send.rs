@@ -0,0 +1,99 @@
+mod common;
+
+use std::cell::Cell;
+use std::future::Future;
+use std::sync::Arc;
+
+use flexi_func_declarative::{block_on, fb};
+
+fn assert_send<F: Future + Send>(future: F) -> F {
+    future
+}
+
+fb!(async(send), pub fetch, (id: u64), -> String, {
+    common::yield_now().await;
+    format!("item-{}", id)
+});
+
+fb!(async(send + 'static), fn owned(values: Vec<u32>) -> u32 {
+    common::yield_now().await;
+    values.into_iter().sum()
+});
+
+fb!(async(send), fn count(text: &str) {
+    common::yield_now().await;
+    assert!(!text.is_empty());
+});
+
+fb!(async(send), error = std::num::ParseIntError, fn parse(text: &str) -> u32 {
+    common::yield_now().await;
+    text.trim().parse::<u32>()?
+});
+
+fb!(async(send), fn fetch_twice(id: u64) -> String {
+    // The helper checking the bound doesn't shadow the function itself.
+    let first = Box::pin(fetch(id)).await;
+    format!("{}, {}", first, fetch(id + 1).await)
+});
+
+struct Client {
+    prefix: String,
+}
+
+impl Client {
+    fb!(async(send), fn get(&self, id: u64) -> String {
+        common::yield_now().await;
+        format!("{}{}", self.prefix, id)
+    });
+}
+
+struct Local {
+    hits: Cell<u32>,
+}
+
+impl Local {
+    fb!(async(send), fn hit(&mut self) -> u32 {
+        common::yield_now().await;
+        self.hits.set(self.hits.get() + 1);
+        self.hits.get()
+    });
+}
+
+#[test]
+fn functions_return_send_futures() {
+    assert_eq!(block_on(assert_send(fetch(1))), "item-1");
+    assert_eq!(block_on(assert_send(owned(vec![1, 2, 3]))), 6);
+    block_on(count("abc"));
+    assert_eq!(block_on(parse(" 4 ")), Ok(4));
+    assert!(block_on(parse("x")).is_err());
+    assert_eq!(block_on(fetch_twice(1)), "item-1, item-2");
+
+    let client = Client {
+        prefix: "client-".to_string(),
+    };
+    assert_eq!(block_on(assert_send(client.get(7))), "client-7");
+
+    let mut local = Local { hits: Cell::new(0) };
+    assert_eq!(block_on(assert_send(local.hit())), 1);
+}
+
+#[test]
+fn closures_return_send_futures() {
+    let shared = Arc::new(5u32);
+    let add = fb!(async(send), closure, |x: u32| -> u32 {
+        common::yield_now().await;
+        x + *shared
+    });
+    assert_eq!(block_on(assert_send(add(1))), 6);
+
+    let label = String::from("done");
+    let spawnable = fb!(async(send + 'static), move closure, || {
+        label.clone()
+    });
+    let handle = std::thread::spawn(move || block_on(spawnable()));
+    assert_eq!(handle.join().unwrap(), "done");
+
+    let borrowed = [1, 2];
+    let sum = fb!(async(send), ref closure, { borrowed.iter().sum::<i32>() });
+    assert_eq!(block_on(sum()), 3);
+}
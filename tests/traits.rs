@@ -0,0 +1,112 @@
+mod common;
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+use flexi_func_declarative::{block_on, fb};
+
+trait Storage: Sync {
+    fb!(trait sync, fn get(&self, key: &str) -> Option<String>;);
+
+    fb!(trait sync, fn put(&mut self, key: &str, value: String););
+
+    fb!(
+        /// Returns the value, or `default` when it's missing.
+        trait sync, fn get_or(&self, key: &str, default: &str) -> String {
+            self.get(key).unwrap_or_else(|| default.to_owned())
+        }
+    );
+
+    fb!(trait sync, fn len_of<K>(&self, key: K) -> usize where K: AsRef<str>;);
+}
+
+trait AsyncStorage: Sync {
+    fb!(trait async, fn get(&self, key: &str) -> Option<String>;);
+
+    fb!(trait async, fn put(&self, key: String, value: String););
+
+    fb!(trait async, fn get_or(&self, key: &str, default: &str) -> String {
+        common::yield_now().await;
+        self.get(key).await.unwrap_or_else(|| default.to_owned())
+    });
+
+    fb!(trait async, error = String, fn require(&self, key: &str) -> String {
+        match self.get(key).await {
+            Some(value) => value,
+            None => return Err(format!("missing `{}`", key)),
+        }
+    });
+
+    fb!(trait async, unsafe fn get_unchecked(&self, key: &str) -> String {
+        self.get(key).await.unwrap_unchecked()
+    });
+
+    fb!(trait async, count, (&self), -> usize, {
+        0
+    });
+}
+
+struct Memory {
+    values: HashMap<String, String>,
+}
+
+impl Storage for Memory {
+    fn get(&self, key: &str) -> Option<String> {
+        self.values.get(key).cloned()
+    }
+
+    fn put(&mut self, key: &str, value: String) {
+        self.values.insert(key.to_owned(), value);
+    }
+
+    fn len_of<K>(&self, key: K) -> usize
+    where
+        K: AsRef<str>,
+    {
+        self.get(key.as_ref()).map_or(0, |value| value.len())
+    }
+}
+
+struct SharedMemory {
+    values: Mutex<HashMap<String, String>>,
+}
+
+impl AsyncStorage for SharedMemory {
+    fb!(async, get, (&self, key: &str), -> Option<String>, {
+        self.values.lock().unwrap().get(key).cloned()
+    });
+
+    fb!(trait async, fn put(&self, key: String, value: String) {
+        self.values.lock().unwrap().insert(key, value);
+    });
+}
+
+fn assert_send<F: Future + Send>(future: F) -> F {
+    future
+}
+
+#[test]
+fn generates_sync_trait_methods() {
+    let mut memory = Memory {
+        values: HashMap::new(),
+    };
+    memory.put("a", "1".to_owned());
+    assert_eq!(memory.get("a"), Some("1".to_owned()));
+    assert_eq!(memory.get_or("b", "2"), "2");
+    assert_eq!(memory.len_of("a"), 1);
+}
+
+#[test]
+fn generates_async_trait_methods() {
+    let memory = SharedMemory {
+        values: Mutex::new(HashMap::new()),
+    };
+    block_on(memory.put("a".to_owned(), "1".to_owned()));
+    assert_eq!(block_on(memory.get("a")), Some("1".to_owned()));
+    assert_eq!(block_on(assert_send(memory.get_or("b", "2"))), "2");
+    assert_eq!(block_on(memory.require("a")), Ok("1".to_owned()));
+    assert_eq!(block_on(memory.require("c")), Err("missing `c`".to_owned()));
+    assert_eq!(block_on(unsafe { memory.get_unchecked("a") }), "1");
+    assert_eq!(block_on(memory.count()), 0);
+}
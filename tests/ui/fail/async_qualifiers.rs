@@ -0,0 +1,11 @@
+use flexi_func_declarative::fb;
+
+fb!(async, const fn answer() -> u32 {
+    42
+});
+
+fb!(async, unsafe extern "C" fn callback() -> i32 {
+    0
+});
+
+fn main() {}
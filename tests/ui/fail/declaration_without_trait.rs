@@ -0,0 +1,5 @@
+use flexi_func_declarative::fb;
+
+fb!(both, fn [load, load_async](key: &str) -> usize;);
+
+fn main() {}
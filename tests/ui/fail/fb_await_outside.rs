@@ -0,0 +1,5 @@
+use flexi_func_declarative::fb_await;
+
+fn main() {
+    let _ = fb_await!(std::future::ready(1));
+}
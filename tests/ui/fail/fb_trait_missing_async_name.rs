@@ -0,0 +1,9 @@
+use flexi_func_declarative::fb_trait;
+
+fb_trait! {
+    pub trait Store {
+        fn get(&self, key: &str) -> Option<String>;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use flexi_func_declarative::ff;
+
+ff! {
+    pub fn compute(data: Vec<u8>) -> usize {
+        data.len()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use flexi_func_declarative::fb;
+
+fb!(both, load, (key: &str), -> usize, {
+    key.len()
+});
+
+fn main() {}
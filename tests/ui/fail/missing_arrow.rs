@@ -0,0 +1,11 @@
+use flexi_func_declarative::fb;
+
+fb!(sync, greet, (name: String), String, {
+    format!("Hello, {}", name)
+});
+
+fb!(sync, fn shout(name: String) String {
+    name.to_uppercase()
+});
+
+fn main() {}
@@ -0,0 +1,10 @@
+use flexi_func_declarative::fb;
+
+fb!(sync, greet, (name: String), -> String, format!("Hello, {}", name));
+
+fb!(sync, fn shout(name: String) -> String name.to_uppercase());
+
+fn main() {
+    let _ = fb!(sync, execute, 1 + 1);
+    let _ = fb!(async, closure, |value: u32| value * 2);
+}
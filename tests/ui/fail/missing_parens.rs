@@ -0,0 +1,7 @@
+use flexi_func_declarative::fb;
+
+fb!(sync, greet, name: String, -> String, {
+    format!("Hello, {}", name)
+});
+
+fn main() {}
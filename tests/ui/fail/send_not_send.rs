@@ -0,0 +1,13 @@
+use std::rc::Rc;
+
+use flexi_func_declarative::fb;
+
+async fn tick() {}
+
+fb!(async(send), fn cached(value: u32) -> u32 {
+    let shared = Rc::new(value);
+    tick().await;
+    *shared
+});
+
+fn main() {}
@@ -0,0 +1,11 @@
+use flexi_func_declarative::fb;
+
+fb!(greet, sync, (name: String), -> String, {
+    format!("Hello, {}", name)
+});
+
+fb!(async, (name: String), shout, -> String, {
+    name.to_uppercase()
+});
+
+fn main() {}
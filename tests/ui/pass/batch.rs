@@ -0,0 +1,40 @@
+use flexi_func_declarative::{block_on, fb, ff};
+
+fb! {
+    async {
+        pub fn user_name(id: u64) -> String {
+            format!("user-{}", id)
+        }
+
+        fn describe<T>(value: T) -> String
+        where
+            T: std::fmt::Display,
+        {
+            value.to_string()
+        }
+    }
+}
+
+fb! {
+    #[inline]
+    sync {
+        const fn square(value: u32) -> u32 {
+            value * value
+        }
+    }
+}
+
+ff! {
+    #[ff(async_name = compute_async)]
+    pub fn compute(data: Vec<u8>) -> usize {
+        data.len()
+    }
+}
+
+fn main() {
+    assert_eq!(block_on(user_name(1)), "user-1");
+    assert_eq!(block_on(describe(2)), "2");
+    assert_eq!(square(3), 9);
+    assert_eq!(compute(vec![1]), 1);
+    assert_eq!(block_on(compute_async(vec![1, 2])), 2);
+}
@@ -0,0 +1,39 @@
+use flexi_func_declarative::{block_on, fb};
+
+fn main() {
+    let sync_closure = fb!(sync, closure, { 1 });
+    assert_eq!(sync_closure(), 1);
+
+    let async_closure = fb!(async, closure, { 2 });
+    assert_eq!(block_on(async_closure()), 2);
+
+    let add = fb!(sync, closure, |a: u32, b: u32| -> u32 { a + b });
+    assert_eq!(add(1, 2), 3);
+
+    let double = fb!(async, closure, |value: u32| { value * 2 });
+    assert_eq!(block_on(double(4)), 8);
+
+    let label = String::from("owned");
+    let owned = fb!(sync, move closure, { label.len() });
+    assert_eq!(std::thread::spawn(owned).join().unwrap(), 5);
+
+    let mut log = Vec::new();
+    block_on(fb!(async, ref execute, { log.push(1) }));
+    assert_eq!(log, [1]);
+
+    let names = vec!["a", "b"];
+    let lengths = fb!(async, ref closure, { names.len() });
+    assert_eq!(block_on(lengths()), 2);
+
+    let value = fb!(sync, execute, { 3 });
+    assert_eq!(value, 3);
+
+    let moved = fb!(sync, move execute, { value + 1 });
+    assert_eq!(moved, 4);
+
+    let future = fb!(async, execute, { 5 });
+    assert_eq!(block_on(future), 5);
+
+    let blocked = fb!(block_on, execute, { fb_await!(std::future::ready(6)) });
+    assert_eq!(blocked, 6);
+}
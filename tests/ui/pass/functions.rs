@@ -0,0 +1,67 @@
+use flexi_func_declarative::{block_on, fb};
+
+fb!(sync, greet, (name: String), -> String, {
+    format!("Hello, {}", name)
+});
+
+fb!(async, pub fetch, (url: &str), -> usize, {
+    url.len()
+});
+
+fb!(sync, log, (lines: &mut Vec<String>, line: &str), {
+    lines.push(line.to_owned());
+});
+
+fb!(
+    /// Parses a value.
+    #[inline]
+    sync, pub(crate) parse<T>, (s: &str), -> Result<T, T::Err>, where T: std::str::FromStr, {
+        s.parse()
+    }
+);
+
+fb!(sync, error = std::num::ParseIntError, parse_u8, (s: &str), -> u8, {
+    s.parse()?
+});
+
+fb!(sync, fn natural(value: u32) -> u32 {
+    value + 1
+});
+
+fb!(sync, pub const unsafe extern "C" fn answer() -> i32 {
+    42
+});
+
+fb!(async, unsafe fn read(ptr: *const u32) -> u32 {
+    *ptr
+});
+
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fb!(sync, bump, (&mut self, by: u32), -> u32, {
+        self.count += by;
+        self.count
+    });
+
+    fb!(async, get, (&self), -> u32, {
+        self.count
+    });
+}
+
+fn main() {
+    assert_eq!(greet("ferris".to_string()), "Hello, ferris");
+    assert_eq!(block_on(fetch("abc")), 3);
+    let mut lines = Vec::new();
+    log(&mut lines, "line");
+    assert_eq!(parse::<u8>("7"), Ok(7));
+    assert_eq!(parse_u8("8"), Ok(8));
+    assert_eq!(natural(1), 2);
+    assert_eq!(unsafe { answer() }, 42);
+    assert_eq!(block_on(unsafe { read(&5) }), 5);
+    let mut counter = Counter { count: 0 };
+    assert_eq!(counter.bump(2), 2);
+    assert_eq!(block_on(counter.get()), 2);
+}
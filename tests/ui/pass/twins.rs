@@ -0,0 +1,31 @@
+use flexi_func_declarative::{block_on, fb};
+
+fb!(both, pub [load, load_async], (key: &str), -> usize, {
+    key.len()
+});
+
+fb!(both, fn [load_twice, load_twice_async](key: &str) -> usize {
+    fb_await!(load(key), load_async(key)) * 2
+});
+
+fb!(async_with_blocking, [fetch, fetch_blocking], (url: String), -> usize, {
+    url.len()
+});
+
+fb!(async_with_blocking(|future| block_on(future)), [count, count_blocking], (items: Vec<u8>), -> usize, {
+    items.len()
+});
+
+fb!(cfg(any()), error = std::num::ParseIntError, configured, (s: &str), -> u32, {
+    fb_await!(s.parse::<u32>(), std::future::ready(s.parse::<u32>()))?
+});
+
+fn main() {
+    assert_eq!(load("ab"), 2);
+    assert_eq!(block_on(load_twice_async("ab")), 4);
+    assert_eq!(load_twice("ab"), 4);
+    assert_eq!(fetch_blocking("abc".to_string()), 3);
+    assert_eq!(block_on(fetch("abcd".to_string())), 4);
+    assert_eq!(count_blocking(vec![1, 2]), 2);
+    assert_eq!(configured("5"), Ok(5));
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use flexi_func_declarative::fb;
+
+mod api {
+    use flexi_func_declarative::fb;
+
+    fb!(sync, pub greet, (name: &str), -> String, {
+        format!("Hello, {}", name)
+    });
+
+    fb!(async, pub greet_async, (name: &str), -> String, {
+        format!("Hello, {}", name)
+    });
+
+    fb!(sync, pub(crate) double, (x: u32), -> u32, {
+        x * 2
+    });
+
+    fb!(async, pub(crate) double_async, (x: u32), -> u32, {
+        x * 2
+    });
+
+    pub mod nested {
+        use flexi_func_declarative::fb;
+
+        fb!(sync, pub(super) triple, (x: u32), -> u32, {
+            x * 3
+        });
+
+        fb!(async, pub(in crate::api) triple_async, (x: u32), -> u32, {
+            x * 3
+        });
+    }
+
+    pub fn triple_both(x: u32) -> (u32, u32) {
+        (nested::triple(x), crate::common::block_on(nested::triple_async(x)))
+    }
+}
+
+fb!(sync, private_helper, (), -> u8, {
+    7
+});
+
+#[test]
+fn pub_functions_are_reachable_from_outside_the_module() {
+    assert_eq!(api::greet("Ferris"), "Hello, Ferris");
+    assert_eq!(common::block_on(api::greet_async("Ferris")), "Hello, Ferris");
+}
+
+#[test]
+fn pub_crate_functions_are_reachable_within_the_crate() {
+    assert_eq!(api::double(4), 8);
+    assert_eq!(common::block_on(api::double_async(4)), 8);
+}
+
+#[test]
+fn restricted_visibility_is_forwarded() {
+    assert_eq!(api::triple_both(3), (9, 9));
+}
+
+#[test]
+fn inherited_visibility_stays_private() {
+    assert_eq!(private_helper(), 7);
+}
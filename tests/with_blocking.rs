@@ -0,0 +1,136 @@
+mod common;
+
+use std::cell::Cell;
+use std::future::Future;
+
+use flexi_func_declarative::{block_on, fb};
+
+thread_local! {
+    static EXECUTOR_CALLS: Cell<u32> = const { Cell::new(0) };
+}
+
+fn counting_executor<F: Future>(future: F) -> F::Output {
+    EXECUTOR_CALLS.with(|calls| calls.set(calls.get() + 1));
+    block_on(future)
+}
+
+fb!(async_with_blocking, pub [fetch, fetch_blocking], (url: String), -> String, {
+    common::yield_now().await;
+    format!("contents of {}", url)
+});
+
+fb!(async_with_blocking(counting_executor), [parse, parse_blocking]<T>, (s: &str), -> Result<T, T::Err>, where T: std::str::FromStr, {
+    common::yield_now().await;
+    s.parse()
+});
+
+struct Runtime;
+
+impl Runtime {
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        block_on(future)
+    }
+}
+
+static RUNTIME: Runtime = Runtime;
+
+fb!(async_with_blocking(|future| RUNTIME.block_on(future)), [sum_pair, sum_pair_blocking], ((a, b): (u32, u32)), -> u32, {
+    let doubled = fb_await!(async { a * 2 });
+    doubled + b
+});
+
+fb!(async_with_blocking, [log_line, log_line_blocking], (log: &mut Vec<String>, line: &str), {
+    common::yield_now().await;
+    log.push(line.to_owned());
+});
+
+fb!(async_with_blocking, [default_text, default_text_blocking]<T: Default + ToString, const N: usize>, (_: [u8; N]), -> String, {
+    common::yield_now().await;
+    T::default().to_string()
+});
+
+struct Client {
+    base: String,
+}
+
+impl Client {
+    fb!(async_with_blocking, pub [get, get_blocking], (&self, path: &str), -> String, {
+        common::yield_now().await;
+        format!("{}/{}", self.base, path)
+    });
+
+    fb!(async_with_blocking, [rebase, rebase_blocking], (&mut self, (scheme, host): (&str, &str)), {
+        common::yield_now().await;
+        self.base = format!("{}://{}", scheme, host);
+    });
+
+    fb!(async_with_blocking, [into_base, into_base_blocking], (self: Box<Self>), -> String, {
+        self.base
+    });
+
+    fb!(async_with_blocking, pub [Self::connect, connect_blocking]<T: ToString>, (host: T), -> Self, {
+        common::yield_now().await;
+        Client { base: format!("https://{}", host.to_string()) }
+    });
+
+    fb!(async_with_blocking(counting_executor), error = String, [Self::connect_checked, connect_checked_blocking], (host: &str), -> Self, {
+        common::yield_now().await;
+        if host.is_empty() {
+            Err("empty host".to_string())?;
+        }
+        Self::connect_blocking(host)
+    });
+}
+
+#[test]
+fn emits_async_function_and_blocking_wrapper() {
+    assert_eq!(block_on(fetch("a".to_string())), "contents of a");
+    assert_eq!(fetch_blocking("b".to_string()), "contents of b");
+}
+
+#[test]
+fn uses_the_given_executor() {
+    assert_eq!(parse_blocking::<u8>("12"), Ok(12));
+    assert!(parse_blocking::<u8>("x").is_err());
+    assert_eq!(EXECUTOR_CALLS.with(Cell::get), 2);
+    assert_eq!(block_on(parse::<u8>("3")), Ok(3));
+    assert_eq!(EXECUTOR_CALLS.with(Cell::get), 2);
+}
+
+#[test]
+fn executor_can_be_a_closure() {
+    assert_eq!(sum_pair_blocking((2, 1)), 5);
+    assert_eq!(block_on(sum_pair((2, 1))), 5);
+}
+
+#[test]
+fn unit_functions_and_methods() {
+    let mut log = vec![];
+    log_line_blocking(&mut log, "blocking");
+    block_on(log_line(&mut log, "async"));
+    assert_eq!(log, ["blocking", "async"]);
+
+    let client = Client { base: "https://example.com".to_string() };
+    assert_eq!(client.get_blocking("a"), "https://example.com/a");
+    assert_eq!(block_on(client.get("b")), "https://example.com/b");
+}
+
+#[test]
+fn wrapper_forwards_generics_patterns_and_receivers() {
+    assert_eq!(default_text_blocking::<u8, 2>([1, 2]), "0");
+    assert_eq!(block_on(default_text::<u8, 1>([1])), "0");
+
+    let mut client = Client { base: String::new() };
+    client.rebase_blocking(("https", "example.org"));
+    assert_eq!(client.base, "https://example.org");
+    block_on(client.rebase(("http", "example.com")));
+    assert_eq!(Box::new(client).into_base_blocking(), "http://example.com");
+}
+
+#[test]
+fn associated_functions_are_called_through_self() {
+    assert_eq!(Client::connect_blocking("example.com").base, "https://example.com");
+    assert_eq!(block_on(Client::connect::<u16>(8080)).base, "https://8080");
+    assert_eq!(Client::connect_checked_blocking("a").map(|client| client.base), Ok("https://a".to_string()));
+    assert!(block_on(Client::connect_checked("")).is_err());
+}